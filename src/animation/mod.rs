@@ -0,0 +1,2 @@
+pub mod sequence;
+pub mod track;
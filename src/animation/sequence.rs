@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glm::Vec3;
+use image::ImageResult;
+
+use crate::camera::camera::Camera;
+use crate::geometry::geometry::Geometry;
+use crate::integrator::path_tracer::PathTracer;
+
+use super::track::Track;
+
+/// Camera flying along keyframed tracks, with times in seconds.
+pub struct CameraAnimation {
+    pub position: Track<Vec3>,
+    pub lookat: Track<Vec3>,
+    /// Vertical field of view in degrees.
+    pub fov: Track<f32>,
+}
+
+impl CameraAnimation {
+    pub fn new(position: Track<Vec3>, lookat: Track<Vec3>, fov: Track<f32>) -> Self {
+        CameraAnimation {
+            position,
+            lookat,
+            fov,
+        }
+    }
+
+    /// `template` at `time`, which gives the image size and every setting the tracks leave
+    /// alone, such as the shutter, lens and color management. It needs a perspective projection.
+    pub fn camera(&self, template: &Camera, time: f32) -> Camera {
+        let mut camera = template.clone();
+        camera.set_view(self.fov.value(time), self.position.value(time));
+        camera.lookat(self.lookat.value(time));
+        camera
+    }
+}
+
+/// Everything needed to render one frame of an animation.
+pub struct Frame<T: Geometry> {
+    pub camera: Camera,
+    pub world: T,
+    pub integrator: PathTracer,
+}
+
+/// Renders `frame_count` frames into `directory`, numbered from `frame_0001.png`. `frame`
+/// builds the scene at the time of each frame, spread evenly from 0 to `duration` seconds so
+/// that the first and last frames land on both ends.
+pub fn render_sequence<T: Geometry, F: Fn(f32) -> Frame<T>>(
+    directory: &Path,
+    frame_count: u32,
+    duration: f32,
+    samples_per_pixel: u32,
+    frame: F,
+) -> ImageResult<()> {
+    fs::create_dir_all(directory)?;
+
+    for index in 0..frame_count {
+        let Frame {
+            camera,
+            world,
+            integrator,
+        } = frame(frame_time(index, frame_count, duration));
+        let image = camera.render_image(&world, &integrator, samples_per_pixel);
+        image.save(frame_path(directory, index + 1))?;
+    }
+
+    Ok(())
+}
+
+fn frame_time(index: u32, frame_count: u32, duration: f32) -> f32 {
+    if frame_count > 1 {
+        duration * index as f32 / (frame_count - 1) as f32
+    } else {
+        0.0
+    }
+}
+
+fn frame_path(directory: &Path, number: u32) -> PathBuf {
+    directory.join(format!("frame_{:04}.png", number))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::track::Interpolation;
+    use crate::geometry::world::World;
+
+    use super::*;
+
+    #[test]
+    fn frames_are_numbered_from_one() {
+        let directory = std::env::temp_dir().join(format!(
+            "render_sequence_frames_are_numbered_{}",
+            std::process::id()
+        ));
+        let mut fov = Track::new(30.0);
+        fov.set_key(1.0, 60.0, Interpolation::Linear);
+        let animation = CameraAnimation::new(
+            Track::new(Vec3::zeros()),
+            Track::new(Vec3::new(0.0, 0.0, -1.0)),
+            fov,
+        );
+
+        let template = Camera::new(4, 2, 30.0, Vec3::zeros());
+
+        render_sequence(&directory, 2, 1.0, 1, |time| Frame {
+            camera: animation.camera(&template, time),
+            world: World::new(vec![]),
+            integrator: PathTracer::new(World::new(vec![]), 4),
+        })
+        .unwrap();
+
+        let frames = [1, 2].map(|number| frame_path(&directory, number));
+        let exists = frames.each_ref().map(|path| path.exists());
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(frames[0], directory.join("frame_0001.png"));
+        assert_eq!(exists, [true, true]);
+    }
+
+    #[test]
+    fn frames_span_the_duration() {
+        let given: Vec<f32> = (0..5).map(|index| frame_time(index, 5, 2.0)).collect();
+
+        assert_eq!(given, [0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(frame_time(0, 1, 2.0), 0.0);
+    }
+}
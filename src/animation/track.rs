@@ -0,0 +1,155 @@
+use glm::Vec3;
+
+use crate::math::decomposed::Decomposed;
+
+/// Values that can be blended between keyframes.
+pub trait Lerp {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        glm::lerp(self, other, t)
+    }
+}
+
+impl Lerp for Decomposed {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.interpolate(other, t)
+    }
+}
+
+/// Timing of the segment from a keyframe to the next one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    Linear,
+    /// Cubic Bezier timing curve from (0, 0) to (1, 1) through the handles (x1, y1) and
+    /// (x2, y2), as in CSS `cubic-bezier`. `Bezier(0.42, 0.0, 0.58, 1.0)` eases in and out.
+    Bezier(f32, f32, f32, f32),
+}
+
+impl Interpolation {
+    /// Maps the fraction `t` of the segment's time to the fraction of the way between values.
+    fn ease(&self, t: f32) -> f32 {
+        match *self {
+            Interpolation::Linear => t,
+            Interpolation::Bezier(x1, y1, x2, y2) => {
+                let bezier = |a: f32, b: f32, s: f32| {
+                    3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s.powi(2) * (1.0 - s) + s.powi(3)
+                };
+
+                // The x coordinate grows monotonically for handles in (0..1), so bisect for it.
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..32 {
+                    let middle = (low + high) / 2.0;
+                    if bezier(x1, x2, middle) < t {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                bezier(y1, y2, (low + high) / 2.0)
+            }
+        }
+    }
+}
+
+struct Keyframe<T> {
+    time: f32,
+    value: T,
+    interpolation: Interpolation,
+}
+
+/// Value animated over time through keyframes. It holds the first and last values before and
+/// after the keyframes.
+pub struct Track<T: Lerp + Clone> {
+    /// Sorted by time.
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Lerp + Clone> Track<T> {
+    pub fn new(value: T) -> Self {
+        Track {
+            keyframes: vec![Keyframe {
+                time: 0.0,
+                value,
+                interpolation: Interpolation::Linear,
+            }],
+        }
+    }
+
+    /// Adds or replaces the keyframe at `time`. `interpolation` leads on to the next keyframe.
+    pub fn set_key(&mut self, time: f32, value: T, interpolation: Interpolation) {
+        let keyframe = Keyframe {
+            time,
+            value,
+            interpolation,
+        };
+
+        match self
+            .keyframes
+            .binary_search_by(|other| other.time.total_cmp(&time))
+        {
+            Ok(index) => self.keyframes[index] = keyframe,
+            Err(index) => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    pub fn value(&self, time: f32) -> T {
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+
+        if next == 0 {
+            return self.keyframes[0].value.clone();
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].value.clone();
+        }
+
+        let from = &self.keyframes[next - 1];
+        let to = &self.keyframes[next];
+        let t = (time - from.time) / (to.time - from.time);
+        from.value.lerp(&to.value, from.interpolation.ease(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_keys_hold_outside_their_range() {
+        let mut track = Track::new(1.0);
+        track.set_key(2.0, 3.0, Interpolation::Linear);
+        track.set_key(1.0, 5.0, Interpolation::Linear);
+
+        let cases = [(-1.0, 1.0), (0.5, 3.0), (1.0, 5.0), (1.5, 4.0), (4.0, 3.0)];
+        for (time, expected) in cases {
+            let given = track.value(time);
+            assert!(
+                (given - expected).abs() < 1e-5,
+                "expected {}, given {}",
+                expected,
+                given
+            );
+        }
+    }
+
+    #[test]
+    fn bezier_eases_in_and_out() {
+        let ease = Interpolation::Bezier(0.42, 0.0, 0.58, 1.0);
+
+        assert!(ease.ease(0.0).abs() < 1e-5);
+        assert!((ease.ease(0.5) - 0.5).abs() < 1e-5);
+        assert!((ease.ease(1.0) - 1.0).abs() < 1e-5);
+        assert!(ease.ease(0.1) < 0.1, "given {}", ease.ease(0.1));
+        assert!(ease.ease(0.9) > 0.9, "given {}", ease.ease(0.9));
+    }
+}
@@ -0,0 +1,215 @@
+use std::f32::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+
+use glm::Vec2;
+use glm::Vec3;
+use image::ImageResult;
+use rand::Rng;
+
+use crate::math::utils::random_vector_in_unit_disk;
+
+/// Grayscale picture of an aperture, brighter pixels letting more light through.
+pub struct ApertureImage {
+    width: u32,
+    height: u32,
+    /// Running sum of the pixel weights in row order, normalized to end at one.
+    cdf: Vec<f32>,
+}
+
+impl ApertureImage {
+    pub fn new(width: u32, height: u32, weights: Vec<f32>) -> Self {
+        assert_eq!(weights.len(), (width * height) as usize);
+
+        let mut total = 0.0;
+        let mut cdf: Vec<f32> = weights
+            .iter()
+            .map(|weight| {
+                total += weight.max(0.0);
+                total
+            })
+            .collect();
+        assert!(total > 0.0, "The aperture image lets no light through");
+        cdf.iter_mut().for_each(|sum| *sum /= total);
+
+        ApertureImage { width, height, cdf }
+    }
+
+    #[allow(dead_code)]
+    pub fn load<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        let image = image::open(path)?.into_luma8();
+        let weights = image
+            .pixels()
+            .map(|pixel| pixel.0[0] as f32 / 255.0)
+            .collect();
+        Ok(ApertureImage::new(image.width(), image.height(), weights))
+    }
+
+    /// Point in (-1..1) on both axes, spread like the brightness of the image, which fills
+    /// the square.
+    fn sample<T: Rng>(&self, rng: &mut T) -> Vec2 {
+        let u: f32 = rng.gen();
+        let index = self
+            .cdf
+            .partition_point(|&sum| sum < u)
+            .min(self.cdf.len() - 1) as u32;
+        let x = (index % self.width) as f32 + rng.gen::<f32>();
+        let y = (index / self.width) as f32 + rng.gen::<f32>();
+        Vec2::new(
+            2.0 * x / self.width as f32 - 1.0,
+            1.0 - 2.0 * y / self.height as f32,
+        )
+    }
+}
+
+/// Shape of the opening light passes through, which out of focus highlights take on.
+#[allow(dead_code)]
+#[derive(Clone)]
+pub enum Aperture {
+    Disk,
+    /// Regular polygon of `blades` corners inscribed in the unit disk, turned by `rotation`
+    /// degrees. Made with `Aperture::polygon`, which checks the number of blades.
+    Polygon {
+        blades: u32,
+        rotation: f32,
+    },
+    Image(Arc<ApertureImage>),
+}
+
+impl Aperture {
+    #[allow(dead_code)]
+    pub fn polygon(blades: u32, rotation: f32) -> Self {
+        assert!(
+            blades >= 3,
+            "A polygonal aperture needs at least three blades"
+        );
+        Aperture::Polygon { blades, rotation }
+    }
+
+    /// Point of the aperture within (-1..1) on both axes: uniform over the area of a disk or
+    /// polygon, and spread like the brightness of an image.
+    pub fn sample<T: Rng>(&self, rng: &mut T) -> Vec2 {
+        match self {
+            Aperture::Disk => random_vector_in_unit_disk(rng).xy(),
+            Aperture::Polygon { blades, rotation } => {
+                // Blades split the polygon into equal triangles around the center.
+                let blade = rng.gen_range(0..*blades) as f32;
+                let corner = |index: f32| {
+                    let angle = rotation.to_radians() + 2.0 * PI * index / *blades as f32;
+                    Vec2::new(angle.cos(), angle.sin())
+                };
+                let (mut u, mut v): (f32, f32) = (rng.gen(), rng.gen());
+                if u + v > 1.0 {
+                    (u, v) = (1.0 - u, 1.0 - v);
+                }
+                corner(blade) * u + corner(blade + 1.0) * v
+            }
+            Aperture::Image(image) => image.sample(rng),
+        }
+    }
+}
+
+/// Lens of some size focused at a distance, which blurs whatever lies nearer or farther.
+#[derive(Clone)]
+pub struct ThinLens {
+    radius: f32,
+    focus_distance: f32,
+    aperture: Aperture,
+    /// How far the lens barrel cuts into the aperture towards the edges of the image, which
+    /// squeezes out of focus highlights there into cat's eyes.
+    cat_eye: f32,
+}
+
+impl ThinLens {
+    /// `radius` is half the width of the aperture, and `focus_distance` the distance to the
+    /// plane in focus.
+    #[allow(dead_code)]
+    pub fn new(radius: f32, focus_distance: f32, aperture: Aperture) -> Self {
+        ThinLens {
+            radius,
+            focus_distance,
+            aperture,
+            cat_eye: 0.0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn set_cat_eye(&mut self, cat_eye: f32) {
+        self.cat_eye = cat_eye;
+    }
+
+    /// Sends the camera space ray through `p_screen` from a random point of the lens instead,
+    /// keeping the point where it crosses the plane in focus. The direction keeps its scale.
+    /// `None` if the lens barrel blocks the ray.
+    pub fn refocus<T: Rng>(
+        &self,
+        rng: &mut T,
+        p_screen: &Vec3,
+        origin: &Vec3,
+        direction: &Vec3,
+    ) -> Option<(Vec3, Vec3)> {
+        let p_aperture = self.aperture.sample(rng);
+        // Without cat's eyes the barrel leaves the whole aperture open, even the corners of an
+        // image aperture outside the unit disk.
+        let barrel_center = -self.cat_eye * p_screen.xy();
+        if self.cat_eye > 0.0 && glm::distance2(&p_aperture, &barrel_center) > 1.0 {
+            return None;
+        }
+
+        let focus = origin + direction * self.focus_distance;
+        let p_lens = origin + Vec3::new(p_aperture.x, p_aperture.y, 0.0) * self.radius;
+        Some((p_lens, (focus - p_lens) / self.focus_distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+
+    use super::*;
+
+    #[test]
+    fn polygon_samples_stay_inside() {
+        let mut rng = ThreadRng::default();
+        let aperture = Aperture::polygon(4, 0.0);
+
+        let samples: Vec<Vec2> = (0..1000).map(|_| aperture.sample(&mut rng)).collect();
+
+        // Corners on the axes make a diamond.
+        assert!(samples.iter().all(|p| p.x.abs() + p.y.abs() <= 1.0 + 1e-5));
+        assert!(samples.iter().any(|p| p.x.abs() + p.y.abs() > 0.9));
+        assert!(samples.iter().any(|p| p.x > 0.0 && p.y > 0.0));
+        assert!(samples.iter().any(|p| p.x < 0.0 && p.y < 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "three blades")]
+    fn polygon_needs_three_blades() {
+        Aperture::polygon(2, 0.0);
+    }
+
+    #[test]
+    fn image_samples_follow_brightness() {
+        let mut rng = ThreadRng::default();
+        let image = ApertureImage::new(2, 2, vec![0.0, 1.0, 0.0, 0.0]);
+        let aperture = Aperture::Image(Arc::new(image));
+
+        for _ in 0..100 {
+            let p = aperture.sample(&mut rng);
+            assert!(p.x >= 0.0 && p.y >= 0.0, "given {:?}", p);
+        }
+    }
+
+    #[test]
+    fn lens_without_cat_eye_keeps_image_corners() {
+        let mut rng = ThreadRng::default();
+        let image = ApertureImage::new(1, 1, vec![1.0]);
+        let lens = ThinLens::new(1.0, 1.0, Aperture::Image(Arc::new(image)));
+        let (origin, direction) = (Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+
+        assert!((0..100).all(|_| {
+            lens.refocus(&mut rng, &Vec3::new(0.9, 0.9, -1.0), &origin, &direction)
+                .is_some()
+        }));
+    }
+}
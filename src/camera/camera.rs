@@ -1,7 +1,11 @@
+use std::f32::consts::PI;
+
 use glm::Mat3;
 use glm::Mat4;
 use glm::Vec3;
 use glm::Vec4;
+use image::Rgb;
+use image::RgbImage;
 use indicatif::ProgressIterator;
 use itertools::Itertools;
 use rand::rngs::ThreadRng;
@@ -9,25 +13,106 @@ use rand::Rng;
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 
+use crate::color::color_management::ColorManagement;
 use crate::geometry::geometry::Geometry;
-use crate::math::interval::Interval;
+use crate::integrator::path_tracer::PathTracer;
 use crate::ray::ray::Ray;
 
+use super::aperture::ThinLens;
+use super::lens::Lens;
+use super::projection::{FisheyeMapping, Projection};
+use super::stereo::Eye;
+
+#[derive(Clone)]
 pub struct Camera {
     image_width: u32,
     image_height: u32,
     position: Vec3,
+    projection: Projection,
     raster_to_camera: Mat3,
     camera_to_world: Mat4,
+    pixel_spread: f32,
+    /// Times at which the shutter opens and closes. Rays are spread evenly between them.
+    shutter: (f32, f32),
+    /// Offset of the eye along the right axis and distance at which the eyes' views meet.
+    eye: Option<(f32, f32)>,
+    lens: Lens,
+    /// Depth of field, or a pinhole that keeps everything sharp if `None`.
+    thin_lens: Option<ThinLens>,
+    color_management: ColorManagement,
 }
 
 impl Camera {
     pub fn new(image_width: u32, image_height: u32, fov: f32, position: Vec3) -> Self {
+        let h = (fov.to_radians() / 2.0).tan();
+        Camera::with_projection(
+            image_width,
+            image_height,
+            h,
+            Projection::Perspective,
+            position,
+        )
+    }
+
+    /// Orthographic camera seeing a view `view_height` units tall.
+    #[allow(dead_code)]
+    pub fn orthographic(
+        image_width: u32,
+        image_height: u32,
+        view_height: f32,
+        position: Vec3,
+    ) -> Self {
+        Camera::with_projection(
+            image_width,
+            image_height,
+            view_height / 2.0,
+            Projection::Orthographic,
+            position,
+        )
+    }
+
+    /// Panoramic camera seeing all around, for VR and for baking environment maps.
+    #[allow(dead_code)]
+    pub fn equirectangular(image_width: u32, image_height: u32, position: Vec3) -> Self {
+        Camera::with_projection(
+            image_width,
+            image_height,
+            1.0,
+            Projection::Equirectangular,
+            position,
+        )
+    }
+
+    /// Fisheye camera whose image circle fits the image height and spans `fov` degrees.
+    #[allow(dead_code)]
+    pub fn fisheye(
+        image_width: u32,
+        image_height: u32,
+        fov: f32,
+        mapping: FisheyeMapping,
+        position: Vec3,
+    ) -> Self {
+        Camera::with_projection(
+            image_width,
+            image_height,
+            1.0,
+            Projection::Fisheye { mapping, fov },
+            position,
+        )
+    }
+
+    /// `h` is half the height of the screen: at unit distance for perspective projections,
+    /// and one for panoramic ones, which take angles from the screen position.
+    fn with_projection(
+        image_width: u32,
+        image_height: u32,
+        h: f32,
+        projection: Projection,
+        position: Vec3,
+    ) -> Self {
         let scale_y = 1.0 / image_height as f32;
         let scale_x = 1.0 / image_width as f32;
         let aspect_ratio = image_width as f32 / image_height as f32;
-        let theta = fov.to_radians();
-        let h = (theta / 2.0).tan();
 
         #[rustfmt::skip]
         // NDC: Normalized Device Coordinates.
@@ -50,7 +135,7 @@ impl Camera {
 
         #[rustfmt::skip]
         // AR: width / height.
-        // H: tan(fov / 2).
+        // H: tan(fov / 2) for perspective, half the view height for orthographic.
         // X axis: (-1..1) remapped to (-AR*H..AR*H).
         // Y axis: (1..-1) remapped to (H..-H).
         let screen_to_camera = Mat3::new(
@@ -58,16 +143,37 @@ impl Camera {
             0.0,              h,   0.0,
             0.0,              0.0, 1.0,
         );
+        // Equirectangular images span all longitudes whatever their aspect ratio.
+        let screen_to_camera = match projection {
+            Projection::Equirectangular => Mat3::identity(),
+            _ => screen_to_camera,
+        };
 
         let raster_to_camera = screen_to_camera * ndc_to_screen * raster_to_ndc;
         let camera_to_world = glm::translation(&position);
+        let pixel_spread = match projection {
+            // Height of a pixel on the image plane at unit distance.
+            Projection::Perspective => 2.0 * h * scale_y,
+            // Parallel rays do not widen with distance.
+            Projection::Orthographic => 0.0,
+            // Angle covered by a pixel.
+            Projection::Equirectangular => PI * scale_y,
+            Projection::Fisheye { fov, .. } => fov.to_radians() * scale_y,
+        };
 
         Camera {
             image_width,
             image_height,
             position,
+            projection,
             raster_to_camera,
             camera_to_world,
+            pixel_spread,
+            shutter: (0.0, 0.0),
+            eye: None,
+            lens: Lens::default(),
+            thin_lens: None,
+            color_management: ColorManagement::default(),
         }
     }
 
@@ -90,10 +196,74 @@ impl Camera {
         self.camera_to_world = camera_to_world;
     }
 
-    pub fn render<T: Geometry>(&self, world: &T, max_depth: u32, samples_per_pixel: u32) {
-        // Print metadata
-        println!("P3\n{} {}\n255", self.image_width, self.image_height);
+    /// Moves a perspective camera to `position` with a vertical field of view of `fov` degrees,
+    /// keeping its other settings. It looks down -z until `lookat` is called again.
+    pub fn set_view(&mut self, fov: f32, position: Vec3) {
+        assert!(
+            self.projection == Projection::Perspective,
+            "Only a perspective projection has a field of view"
+        );
+        let view = Camera::new(self.image_width, self.image_height, fov, position);
+        self.position = position;
+        self.raster_to_camera = view.raster_to_camera;
+        self.camera_to_world = view.camera_to_world;
+        self.pixel_spread = view.pixel_spread;
+    }
+
+    /// Moving geometry blurs over the times from `open` to `close`, where it usually moves
+    /// from time 0 to 1.
+    pub fn set_shutter(&mut self, open: f32, close: f32) {
+        self.shutter = (open, close);
+    }
+
+    /// Moves the camera to one eye of a pair `ipd` apart whose views meet at `convergence`,
+    /// or stay parallel if it is infinite.
+    pub fn set_eye(&mut self, eye: Eye, ipd: f32, convergence: f32) {
+        let offset = match eye {
+            Eye::Left => -ipd / 2.0,
+            Eye::Right => ipd / 2.0,
+        };
+        self.eye = Some((offset, convergence));
+    }
+
+    /// Distortion and chromatic aberration work on the image plane, so they need a perspective
+    /// projection.
+    #[allow(dead_code)]
+    pub fn set_lens(&mut self, lens: Lens) {
+        assert!(
+            self.projection == Projection::Perspective || !lens.bends_rays(),
+            "Lens distortion and chromatic aberration need a perspective projection"
+        );
+        self.lens = lens;
+    }
+
+    /// The lens sits in the plane of the image, across the view direction of a perspective
+    /// projection.
+    #[allow(dead_code)]
+    pub fn set_thin_lens(&mut self, thin_lens: ThinLens) {
+        assert!(
+            self.projection == Projection::Perspective,
+            "A thin lens needs a perspective projection"
+        );
+        self.thin_lens = Some(thin_lens);
+    }
+
+    pub fn set_color_management(&mut self, color_management: ColorManagement) {
+        self.color_management = color_management;
+    }
 
+    pub fn render<T: Geometry>(&self, world: &T, integrator: &PathTracer, samples_per_pixel: u32) {
+        let image = self.render_image(world, integrator, samples_per_pixel);
+        print_image(&image);
+    }
+
+    /// Renders into a display encoded image rather than printing it.
+    pub fn render_image<T: Geometry>(
+        &self,
+        world: &T,
+        integrator: &PathTracer,
+        samples_per_pixel: u32,
+    ) -> RgbImage {
         let pixels = (0..self.image_height)
             .progress()
             .cartesian_product(0..self.image_width)
@@ -102,52 +272,62 @@ impl Camera {
                     .into_par_iter()
                     .map(|_| {
                         let mut rng = ThreadRng::default();
-                        let ray = self.get_ray(&mut rng, x, y);
-                        Camera::ray_color(&ray, world, max_depth)
+                        self.get_ray(&mut rng, x, y)
+                            .map_or(Vec3::zeros(), |(ray, weight)| {
+                                integrator.ray_color(&ray, world).component_mul(&weight)
+                            })
                     })
                     .sum();
                 pixel_color / samples_per_pixel as f32
             });
 
-        for pixel_color in pixels {
-            Camera::write_color(pixel_color);
+        let mut rng = ThreadRng::default();
+        let mut image = RgbImage::new(self.image_width, self.image_height);
+        for (pixel, pixel_color) in image.pixels_mut().zip(pixels) {
+            *pixel = Rgb(self.color_management.encode(&mut rng, &pixel_color));
         }
+        image
     }
 
-    fn ray_color<T: Geometry>(ray: &Ray, world: &T, depth: u32) -> Vec3 {
-        if depth <= 0 {
-            Vec3::repeat(0.0)
-        } else if let Some(hit_record) = world.hit(&ray, &Interval::new(0.001, f32::MAX)) {
-            if let Some((scattered_ray, attenuation)) =
-                hit_record.material.scatter(ray, &hit_record)
-            {
-                attenuation.component_mul(&Camera::ray_color(&scattered_ray, world, depth - 1))
-            } else {
-                Vec3::repeat(0.0)
-            }
-        } else {
-            let unit_direction = ray.direction;
-            let a = 0.5 * (unit_direction.y + 1.0);
-            glm::lerp(&Vec3::repeat(1.0), &Vec3::new(0.5, 0.7, 1.0), a)
-        }
-    }
-
-    fn get_ray<T: Rng>(&self, rng: &mut T, x: u32, y: u32) -> Ray {
+    /// Ray through a random point of pixel (`x`, `y`) with the weight of its color, or `None`
+    /// if the pixel sees nothing.
+    fn get_ray<T: Rng>(&self, rng: &mut T, x: u32, y: u32) -> Option<(Ray, Vec3)> {
         let offset_x: f32 = rng.gen();
         let offset_y: f32 = rng.gen();
         let p_screen = Vec3::new(x as f32 + offset_x, y as f32 + offset_y, 1.0);
-        let p_camera = self.raster_to_camera * p_screen;
-        let direction = self.camera_to_world * Vec4::new(p_camera.x, p_camera.y, -1.0, 0.0);
-        Ray::new(self.position, direction.xyz().normalize())
+        let (magnification, weight) = self.lens.sample_channel(rng);
+        let p_camera = self
+            .lens
+            .ideal_point(&(self.raster_to_camera * p_screen), magnification);
+        let (mut origin, mut direction) = self.projection.camera_ray(&p_camera)?;
+        if let Some(thin_lens) = &self.thin_lens {
+            (origin, direction) = thin_lens.refocus(rng, &p_camera, &origin, &direction)?;
+        }
+        if let Some((offset, convergence)) = self.eye {
+            let target = origin + direction * convergence;
+            origin += self.projection.eye_axis(&p_camera) * offset;
+            if convergence.is_finite() {
+                direction = target - origin;
+            }
+        }
+        let origin = self.camera_to_world * Vec4::new(origin.x, origin.y, origin.z, 1.0);
+        let weight = weight * self.lens.transmission(&direction);
+        let direction = self.camera_to_world * glm::vec3_to_vec4(&direction);
+        let mut ray = Ray::new(origin.xyz(), direction.xyz().normalize());
+        ray.spread = self.pixel_spread;
+        let (open, close) = self.shutter;
+        ray.time = open + rng.gen::<f32>() * (close - open);
+        Some((ray, weight))
     }
+}
 
-    fn write_color(color: Vec3) {
-        let gamma_corrected = glm::sqrt(&color);
-        let rgb_color = glm::clamp(&gamma_corrected, 0.0, 1.0) * 255.0;
-        println!(
-            "{} {} {}",
-            rgb_color.x as u32, rgb_color.y as u32, rgb_color.z as u32
-        );
+/// Prints the image to stdout as a plain PPM.
+pub fn print_image(image: &RgbImage) {
+    // Print metadata
+    println!("P3\n{} {}\n255", image.width(), image.height());
+
+    for Rgb([r, g, b]) in image.pixels() {
+        println!("{} {} {}", r, g, b);
     }
 }
 
@@ -155,13 +335,16 @@ impl Camera {
 mod tests {
     use rand::rngs::mock::StepRng;
 
+    use crate::camera::aperture::Aperture;
+    use crate::camera::lens::Distortion;
+
     use super::*;
 
     #[test]
     fn square_camera_test_00() {
         let mut rng = StepRng::new(0, 0);
         let camera = Camera::new(10, 10, 90.0, Vec3::repeat(0.0));
-        let ray = camera.get_ray(&mut rng, 0, 0);
+        let (ray, _) = camera.get_ray(&mut rng, 0, 0).unwrap();
 
         let expected = Vec3::new(-1.0, 1.0, -1.0).normalize();
         let given = ray.direction;
@@ -180,7 +363,7 @@ mod tests {
     fn square_camera_test99() {
         let mut rng = StepRng::new(0, 0);
         let camera = Camera::new(10, 10, 90.0, Vec3::repeat(0.0));
-        let ray = camera.get_ray(&mut rng, 9, 9);
+        let (ray, _) = camera.get_ray(&mut rng, 9, 9).unwrap();
 
         let expected = Vec3::new(0.8, -0.8, -1.0).normalize();
         let given = ray.direction;
@@ -199,7 +382,7 @@ mod tests {
     fn rectangular_camera_test00() {
         let mut rng = StepRng::new(0, 0);
         let camera = Camera::new(20, 10, 90.0, Vec3::repeat(0.0));
-        let ray = camera.get_ray(&mut rng, 0, 0);
+        let (ray, _) = camera.get_ray(&mut rng, 0, 0).unwrap();
 
         let expected = Vec3::new(-2.0, 1.0, -1.0).normalize();
         let given = ray.direction;
@@ -218,7 +401,7 @@ mod tests {
     fn rectangular_camera_test99() {
         let mut rng = StepRng::new(0, 0);
         let camera = Camera::new(20, 10, 90.0, Vec3::repeat(0.0));
-        let ray = camera.get_ray(&mut rng, 19, 9);
+        let (ray, _) = camera.get_ray(&mut rng, 19, 9).unwrap();
 
         let expected = Vec3::new(1.8, -0.8, -1.0).normalize();
         let given = ray.direction;
@@ -232,4 +415,154 @@ mod tests {
             given
         )
     }
+
+    #[test]
+    fn orthographic_camera_test00() {
+        let mut rng = StepRng::new(0, 0);
+        let camera = Camera::orthographic(20, 10, 2.0, Vec3::new(0.0, 0.0, 5.0));
+        let (ray, _) = camera.get_ray(&mut rng, 0, 0).unwrap();
+
+        let cases = [
+            (Vec3::new(-2.0, 1.0, 5.0), ray.origin),
+            (Vec3::new(0.0, 0.0, -1.0), ray.direction),
+        ];
+        for (expected, given) in cases {
+            assert!(
+                glm::equal_eps(&expected, &given, glm::epsilon())
+                    .iter()
+                    .all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                given
+            )
+        }
+    }
+
+    #[test]
+    fn equirectangular_camera_test() {
+        let mut rng = StepRng::new(0, 0);
+        let camera = Camera::equirectangular(20, 10, Vec3::repeat(0.0));
+
+        let cases = [
+            ((0, 0), Vec3::new(0.0, 1.0, 0.0)),
+            ((10, 5), Vec3::new(0.0, 0.0, -1.0)),
+        ];
+        for ((x, y), expected) in cases {
+            let given = camera.get_ray(&mut rng, x, y).unwrap().0.direction;
+            assert!(
+                glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                given
+            )
+        }
+    }
+
+    #[test]
+    fn fisheye_camera_misses_outside_image_circle() {
+        let mut rng = StepRng::new(0, 0);
+        let camera = Camera::fisheye(20, 10, 180.0, FisheyeMapping::Equidistant, Vec3::zeros());
+
+        assert!(camera.get_ray(&mut rng, 0, 0).is_none());
+        assert!(camera.get_ray(&mut rng, 10, 5).is_some());
+    }
+
+    #[test]
+    fn stereo_eyes_converge() {
+        let mut rng = StepRng::new(0, 0);
+        let mut camera = Camera::new(10, 10, 90.0, Vec3::zeros());
+
+        for (eye, x) in [(Eye::Left, -0.05), (Eye::Right, 0.05)] {
+            camera.set_eye(eye, 0.1, 2.0);
+            let (ray, _) = camera.get_ray(&mut rng, 5, 5).unwrap();
+
+            let cases = [
+                (Vec3::new(x, 0.0, 0.0), ray.origin),
+                (
+                    Vec3::new(0.0, 0.0, -2.0),
+                    ray.at(2.0 / ray.direction.z.abs()),
+                ),
+            ];
+            for (expected, given) in cases {
+                assert!(
+                    glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+                    "expected {:?}, given {:?}",
+                    expected,
+                    given
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn omni_directional_stereo_turns_eyes() {
+        let mut rng = StepRng::new(0, 0);
+        let mut camera = Camera::equirectangular(20, 10, Vec3::zeros());
+        camera.set_eye(Eye::Right, 0.1, f32::INFINITY);
+
+        // Looking forward, then to the right along +X.
+        let cases = [
+            ((10, 5), Vec3::new(0.05, 0.0, 0.0)),
+            ((15, 5), Vec3::new(0.0, 0.0, 0.05)),
+        ];
+        for ((x, y), expected) in cases {
+            let (ray, _) = camera.get_ray(&mut rng, x, y).unwrap();
+            let given = ray.origin;
+            assert!(
+                glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                given
+            )
+        }
+    }
+
+    #[test]
+    fn thin_lens_focuses_on_plane() {
+        let mut rng = ThreadRng::default();
+        let mut camera = Camera::new(10, 10, 90.0, Vec3::zeros());
+        camera.set_thin_lens(ThinLens::new(0.5, 3.0, Aperture::polygon(6, 0.0)));
+
+        let rays: Vec<Ray> = (0..100)
+            .map(|_| camera.get_ray(&mut rng, 5, 5).unwrap().0)
+            .collect();
+
+        // Rays leave from all over the lens but meet within the pixel on the plane in focus.
+        assert!(rays.iter().any(|ray| ray.origin.norm() > 0.25));
+        for ray in rays {
+            let focus = ray.at(3.0 / ray.direction.z.abs());
+            assert!((focus.z + 3.0).abs() < 1e-4, "given {:?}", focus);
+            assert!((0.0..=0.6).contains(&focus.x), "given {:?}", focus);
+            assert!((-0.6..=0.0).contains(&focus.y), "given {:?}", focus);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "perspective projection")]
+    fn distorting_lens_needs_perspective() {
+        let mut camera = Camera::equirectangular(20, 10, Vec3::zeros());
+        let distortion = Distortion::new([-0.1, 0.0, 0.0], [0.0, 0.0]);
+        camera.set_lens(Lens::new(distortion, 0.0, 0.0));
+    }
+
+    #[test]
+    fn set_view_keeps_settings() {
+        let mut camera = Camera::new(4, 2, 90.0, Vec3::zeros());
+        camera.set_shutter(0.2, 0.4);
+        let expected = Camera::new(4, 2, 30.0, Vec3::new(1.0, 2.0, 3.0));
+
+        camera.set_view(30.0, Vec3::new(1.0, 2.0, 3.0));
+
+        assert_eq!(camera.shutter, (0.2, 0.4));
+        assert_eq!(camera.camera_to_world, expected.camera_to_world);
+        assert_eq!(camera.raster_to_camera, expected.raster_to_camera);
+        assert_eq!(camera.pixel_spread, expected.pixel_spread);
+    }
+
+    #[test]
+    #[should_panic(expected = "perspective projection")]
+    fn thin_lens_needs_perspective() {
+        let mut camera = Camera::equirectangular(20, 10, Vec3::zeros());
+        camera.set_thin_lens(ThinLens::new(0.1, 1.0, Aperture::Disk));
+    }
 }
@@ -0,0 +1,258 @@
+use glm::Mat2;
+use glm::Vec2;
+use glm::Vec3;
+use rand::Rng;
+
+/// Brown–Conrady model of how a real lens bends straight lines, over normalized image
+/// coordinates: the image plane at unit distance, centered on the optical axis. Coefficients
+/// are in the order calibration tools such as OpenCV report them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distortion {
+    /// k1, k2 and k3. Negative k1 bulges the image into a barrel, positive pinches a cushion.
+    radial: [f32; 3],
+    /// p1 and p2, for a lens not quite parallel to the sensor.
+    tangential: [f32; 2],
+    /// Radius up to which the radial distortion grows monotonically, so that it can be
+    /// inverted. Past it the polynomial folds back onto the image.
+    valid_radius: f32,
+}
+
+impl Distortion {
+    #[allow(dead_code)]
+    pub fn new(radial: [f32; 3], tangential: [f32; 2]) -> Self {
+        Distortion {
+            radial,
+            tangential,
+            valid_radius: Distortion::monotonic_radius(radial),
+        }
+    }
+
+    /// Where the lens images the point that an ideal lens images at `p`.
+    pub fn distort(&self, p: &Vec2) -> Vec2 {
+        p * self.radial_scale(p) + self.tangential_offset(p)
+    }
+
+    /// Inverse of `distort`, found by Newton iteration. Points the lens cannot image, past the
+    /// distorted valid radius, map to the edge of the valid region.
+    pub fn undistort(&self, p: &Vec2) -> Vec2 {
+        if *self == Distortion::default() {
+            return *p;
+        }
+
+        let mut undistorted = self.clamp(p);
+        for _ in 0..32 {
+            let residual = self.distort(&undistorted) - p;
+            if residual.norm() < 1e-6 {
+                break;
+            }
+            let step = match self.jacobian(&undistorted).try_inverse() {
+                Some(inverse) => inverse * residual,
+                None => break,
+            };
+            undistorted = self.clamp(&(undistorted - step));
+        }
+        undistorted
+    }
+
+    /// First radius at which the derivative of `r * radial_scale(r)` vanishes, or infinity.
+    fn monotonic_radius(radial: [f32; 3]) -> f32 {
+        let [k1, k2, k3] = radial;
+        let slope = |r: f32| {
+            let r2 = r * r;
+            1.0 + r2 * (3.0 * k1 + r2 * (5.0 * k2 + r2 * 7.0 * k3))
+        };
+
+        let step = 0.01;
+        let Some(steps) = (1..=1000).find(|&i| slope(i as f32 * step) <= 0.0) else {
+            return f32::INFINITY;
+        };
+        let (mut low, mut high) = ((steps - 1) as f32 * step, steps as f32 * step);
+        for _ in 0..24 {
+            let middle = (low + high) / 2.0;
+            if slope(middle) > 0.0 {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    fn clamp(&self, p: &Vec2) -> Vec2 {
+        let radius = p.norm();
+        if radius > self.valid_radius {
+            p * (self.valid_radius / radius)
+        } else {
+            *p
+        }
+    }
+
+    fn radial_scale(&self, p: &Vec2) -> f32 {
+        let [k1, k2, k3] = self.radial;
+        let r2 = p.norm_squared();
+        1.0 + r2 * (k1 + r2 * (k2 + r2 * k3))
+    }
+
+    fn tangential_offset(&self, p: &Vec2) -> Vec2 {
+        let [p1, p2] = self.tangential;
+        let r2 = p.norm_squared();
+        Vec2::new(
+            2.0 * p1 * p.x * p.y + p2 * (r2 + 2.0 * p.x * p.x),
+            p1 * (r2 + 2.0 * p.y * p.y) + 2.0 * p2 * p.x * p.y,
+        )
+    }
+
+    /// Derivatives of `distort` at `p`.
+    fn jacobian(&self, p: &Vec2) -> Mat2 {
+        let [k1, k2, k3] = self.radial;
+        let [p1, p2] = self.tangential;
+        let (x, y) = (p.x, p.y);
+        let r2 = p.norm_squared();
+        let scale = self.radial_scale(p);
+        // Derivative of the radial scale with respect to r².
+        let slope = k1 + r2 * (2.0 * k2 + r2 * 3.0 * k3);
+        let cross = 2.0 * x * y * slope + 2.0 * p1 * x + 2.0 * p2 * y;
+
+        Mat2::new(
+            scale + 2.0 * x * x * slope + 2.0 * p1 * y + 6.0 * p2 * x,
+            cross,
+            cross,
+            scale + 2.0 * y * y * slope + 6.0 * p1 * y + 2.0 * p2 * x,
+        )
+    }
+}
+
+impl Default for Distortion {
+    fn default() -> Self {
+        Distortion::new([0.0; 3], [0.0; 2])
+    }
+}
+
+/// Flaws of a physical lens, to match renders with footage. The default lens is ideal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lens {
+    distortion: Distortion,
+    /// How much of the natural cos⁴ falloff of light towards the edges applies, from 0 to 1.
+    vignetting: f32,
+    /// Difference in magnification of the red and blue channels from the green one. Positive
+    /// values fringe edges with red outwards and blue inwards.
+    chromatic_aberration: f32,
+}
+
+impl Lens {
+    #[allow(dead_code)]
+    pub fn new(distortion: Distortion, vignetting: f32, chromatic_aberration: f32) -> Self {
+        Lens {
+            distortion,
+            vignetting,
+            chromatic_aberration,
+        }
+    }
+
+    /// Whether the lens moves points on the image, which only makes sense on the image plane of
+    /// a perspective projection.
+    pub fn bends_rays(&self) -> bool {
+        self.distortion != Distortion::default() || self.chromatic_aberration != 0.0
+    }
+
+    /// Picks the color channels a ray carries: all of them without chromatic aberration, or
+    /// else one at random. Returns the magnification of their image and the weight of the
+    /// ray's color.
+    pub fn sample_channel<T: Rng>(&self, rng: &mut T) -> (f32, Vec3) {
+        if self.chromatic_aberration == 0.0 {
+            return (1.0, Vec3::repeat(1.0));
+        }
+
+        let channel = rng.gen_range(0..3);
+        let magnification = 1.0 + self.chromatic_aberration * (1.0 - channel as f32);
+        let mut weight = Vec3::zeros();
+        weight[channel] = 3.0;
+        (magnification, weight)
+    }
+
+    /// Point on the screen of an ideal lens that is seen at `p_screen` through this lens, by a
+    /// channel with `magnification`.
+    pub fn ideal_point(&self, p_screen: &Vec3, magnification: f32) -> Vec3 {
+        let p = self.distortion.undistort(&(p_screen.xy() / magnification));
+        Vec3::new(p.x, p.y, p_screen.z)
+    }
+
+    /// Fraction of the light arriving along `direction`, in camera space, that reaches the
+    /// sensor.
+    pub fn transmission(&self, direction: &Vec3) -> f32 {
+        let cos_theta = -direction.z / direction.norm();
+        1.0 - self.vignetting * (1.0 - cos_theta.max(0.0).powi(4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: &Vec2, given: &Vec2) {
+        assert!(
+            glm::equal_eps(expected, given, 1e-4).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn undistort_inverts_distort() {
+        let distortion = Distortion::new([-0.3, 0.1, -0.02], [0.001, -0.002]);
+        let expected = Vec2::new(0.6, -0.4);
+        let distorted = distortion.distort(&expected);
+
+        assert!(distorted.norm() < expected.norm());
+        assert_close(&expected, &distortion.undistort(&distorted));
+    }
+
+    #[test]
+    fn undistort_reaches_image_corners() {
+        // Corners of a 90° frame at 16:9, and of the same frame tilted.
+        let corners = [
+            Vec2::new(1.78, 1.0),
+            Vec2::new(-1.78, -1.0),
+            Vec2::new(1.0, 0.56),
+        ];
+        let distortion = Distortion::new([-0.05, 0.01, 0.0], [0.002, -0.001]);
+
+        for corner in corners {
+            let undistorted = distortion.undistort(&corner);
+            assert_close(&corner, &distortion.distort(&undistorted));
+        }
+    }
+
+    #[test]
+    fn undistort_clamps_past_valid_radius() {
+        let distortion = Distortion::new([-0.3, 0.1, -0.02], [0.0, 0.0]);
+        let edge = distortion.distort(&Vec2::new(distortion.valid_radius, 0.0));
+
+        for corner in [Vec2::new(1.78, 1.0), Vec2::new(1.0, 0.56)] {
+            let undistorted = distortion.undistort(&corner);
+            let radius = undistorted.norm();
+
+            assert!(
+                (radius - distortion.valid_radius).abs() < 1e-3,
+                "expected {}, given {}",
+                distortion.valid_radius,
+                radius
+            );
+            // The farthest point the lens reaches in that direction.
+            assert_close(
+                &(corner.normalize() * edge.x),
+                &distortion.distort(&undistorted),
+            );
+        }
+    }
+
+    #[test]
+    fn vignetting_follows_cos4() {
+        let lens = Lens::new(Distortion::default(), 1.0, 0.0);
+        let direction = Vec3::new(3.0_f32.sqrt(), 0.0, -1.0);
+
+        assert!((lens.transmission(&Vec3::new(0.0, 0.0, -1.0)) - 1.0).abs() < 1e-5);
+        assert!((lens.transmission(&direction) - 1.0 / 16.0).abs() < 1e-5);
+    }
+}
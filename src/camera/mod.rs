@@ -1 +1,6 @@
+pub mod aperture;
+#[allow(clippy::module_inception)]
 pub mod camera;
+pub mod lens;
+pub mod projection;
+pub mod stereo;
@@ -0,0 +1,124 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use glm::Vec3;
+
+/// How a lens spreads the angle from the view direction over the radius of the image circle.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FisheyeMapping {
+    /// Radius proportional to the angle, which keeps angular distances even.
+    Equidistant,
+    /// Radius proportional to `sin(angle / 2)`, which keeps areas even.
+    Equisolid,
+}
+
+/// How points on the screen turn into rays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Rays fan out from the camera position through the image plane at unit distance.
+    Perspective,
+    /// Parallel rays start on the screen itself, so sizes do not change with distance.
+    Orthographic,
+    /// Longitude across the width and latitude down the height, covering every direction.
+    Equirectangular,
+    /// Circular fisheye whose image circle fits the height and spans `fov` degrees.
+    Fisheye { mapping: FisheyeMapping, fov: f32 },
+}
+
+impl Projection {
+    /// Origin and direction in camera space of the ray through `p_screen`, a point on the
+    /// screen scaled as set up by `Camera`. `None` outside of a fisheye's image circle.
+    pub fn camera_ray(&self, p_screen: &Vec3) -> Option<(Vec3, Vec3)> {
+        let (x, y) = (p_screen.x, p_screen.y);
+
+        match *self {
+            Projection::Perspective => Some((Vec3::zeros(), Vec3::new(x, y, -1.0))),
+            Projection::Orthographic => Some((Vec3::new(x, y, 0.0), Vec3::new(0.0, 0.0, -1.0))),
+            Projection::Equirectangular => {
+                Some((Vec3::zeros(), spherical_direction(x * PI, y * FRAC_PI_2)))
+            }
+            Projection::Fisheye { mapping, fov } => {
+                let r = (x.powi(2) + y.powi(2)).sqrt();
+                if r > 1.0 {
+                    return None;
+                }
+
+                let max_theta = fov.to_radians() / 2.0;
+                let theta = match mapping {
+                    FisheyeMapping::Equidistant => r * max_theta,
+                    FisheyeMapping::Equisolid => 2.0 * (r * (max_theta / 2.0).sin()).asin(),
+                };
+                let phi = y.atan2(x);
+                let direction = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    -theta.cos(),
+                );
+                Some((Vec3::zeros(), direction))
+            }
+        }
+    }
+
+    /// Axis along which the eyes of a stereo pair sit for the ray through `p_screen`.
+    /// Panoramas turn it with the longitude so that the eyes see in stereo all around, as in
+    /// omni-directional stereo.
+    pub fn eye_axis(&self, p_screen: &Vec3) -> Vec3 {
+        match *self {
+            Projection::Equirectangular => {
+                let longitude = p_screen.x * PI;
+                Vec3::new(longitude.cos(), 0.0, longitude.sin())
+            }
+            _ => Vec3::x(),
+        }
+    }
+}
+
+/// Direction at `longitude` around the up axis, zero straight ahead, and `latitude` above the
+/// horizon.
+pub fn spherical_direction(longitude: f32, latitude: f32) -> Vec3 {
+    Vec3::new(
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+        -latitude.cos() * longitude.cos(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fisheye_mappings() {
+        let fov = 180.0;
+        let cases = [
+            (FisheyeMapping::Equidistant, 0.5, 45.0_f32.to_radians()),
+            (
+                FisheyeMapping::Equisolid,
+                0.5,
+                2.0 * (0.5 * 45.0_f32.to_radians().sin()).asin(),
+            ),
+            (FisheyeMapping::Equisolid, 1.0, FRAC_PI_2),
+        ];
+
+        for (mapping, r, theta) in cases {
+            let projection = Projection::Fisheye { mapping, fov };
+            let (_, direction) = projection.camera_ray(&Vec3::new(0.0, r, 1.0)).unwrap();
+            let expected = Vec3::new(0.0, theta.sin(), -theta.cos());
+
+            assert!(
+                glm::equal_eps(&expected, &direction, 1e-5)
+                    .iter()
+                    .all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                direction
+            );
+        }
+
+        let projection = Projection::Fisheye {
+            mapping: FisheyeMapping::Equidistant,
+            fov,
+        };
+        assert!(projection.camera_ray(&Vec3::new(1.0, 1.0, 1.0)).is_none());
+    }
+}
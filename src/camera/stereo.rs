@@ -0,0 +1,111 @@
+use image::GenericImage;
+use image::RgbImage;
+
+use crate::geometry::geometry::Geometry;
+use crate::integrator::path_tracer::PathTracer;
+
+use super::camera::{print_image, Camera};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+/// How the views of both eyes are packed into one image. The left eye always comes first.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StereoLayout {
+    SideBySide,
+    TopBottom,
+}
+
+/// Pair of eyes rendering a camera's view in stereo, for headsets and 3D displays. With an
+/// equirectangular camera it renders omni-directional stereo panoramas.
+pub struct StereoRig {
+    /// Interpupillary distance, around 0.064 in a scene measured in metres.
+    ipd: f32,
+    /// Distance at which both views meet and objects appear at the depth of the screen.
+    convergence: f32,
+    layout: StereoLayout,
+}
+
+impl StereoRig {
+    /// The eyes look parallel if `convergence` is infinite.
+    pub fn new(ipd: f32, convergence: f32, layout: StereoLayout) -> Self {
+        StereoRig {
+            ipd,
+            convergence,
+            layout,
+        }
+    }
+
+    /// `camera` moved to `eye`, keeping its view direction.
+    pub fn camera(&self, camera: &Camera, eye: Eye) -> Camera {
+        let mut camera = camera.clone();
+        camera.set_eye(eye, self.ipd, self.convergence);
+        camera
+    }
+
+    pub fn render<T: Geometry>(
+        &self,
+        camera: &Camera,
+        world: &T,
+        integrator: &PathTracer,
+        samples_per_pixel: u32,
+    ) {
+        let image = self.render_image(camera, world, integrator, samples_per_pixel);
+        print_image(&image);
+    }
+
+    pub fn render_image<T: Geometry>(
+        &self,
+        camera: &Camera,
+        world: &T,
+        integrator: &PathTracer,
+        samples_per_pixel: u32,
+    ) -> RgbImage {
+        let [left, right] = [Eye::Left, Eye::Right].map(|eye| {
+            self.camera(camera, eye)
+                .render_image(world, integrator, samples_per_pixel)
+        });
+        let (width, height) = left.dimensions();
+
+        let (mut image, right_x, right_y) = match self.layout {
+            StereoLayout::SideBySide => (RgbImage::new(2 * width, height), width, 0),
+            StereoLayout::TopBottom => (RgbImage::new(width, 2 * height), 0, height),
+        };
+        image
+            .copy_from(&left, 0, 0)
+            .expect("The left view fits in the image");
+        image
+            .copy_from(&right, right_x, right_y)
+            .expect("The right view fits in the image");
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::geometry::world::World;
+
+    use super::*;
+
+    #[test]
+    fn layouts_pack_both_views() {
+        let camera = Camera::new(4, 2, 90.0, Vec3::zeros());
+        let integrator = PathTracer::new(World::new(vec![]), 4);
+
+        let cases = [
+            (StereoLayout::SideBySide, (8, 2)),
+            (StereoLayout::TopBottom, (4, 4)),
+        ];
+        for (layout, expected) in cases {
+            let rig = StereoRig::new(0.064, f32::INFINITY, layout);
+            let image = rig.render_image(&camera, &World::new(vec![]), &integrator, 1);
+            assert_eq!(image.dimensions(), expected);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use glm::Vec3;
+use rand::Rng;
+
+use super::color_space::ColorSpace;
+use super::transfer::TransferFunction;
+
+/// Output transforms for the displays we target. All of them use Rec.709 primaries.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DisplayTransform {
+    /// Computer monitors and compositors expecting sRGB encoded images.
+    Srgb,
+    /// BT.1886 broadcast displays (gamma 2.4).
+    Rec1886,
+    /// No encoding, for debugging linear values.
+    Linear,
+}
+
+impl DisplayTransform {
+    fn transfer_function(&self) -> TransferFunction {
+        match self {
+            DisplayTransform::Srgb => TransferFunction::Srgb,
+            DisplayTransform::Rec1886 => TransferFunction::Gamma(2.4),
+            DisplayTransform::Linear => TransferFunction::Linear,
+        }
+    }
+}
+
+/// Describes how linear radiance in the working space becomes display referred 8-bit values.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorManagement {
+    pub working_space: ColorSpace,
+    pub display: DisplayTransform,
+    /// Adds triangular noise of one quantization step before rounding, which hides banding.
+    pub dither: bool,
+}
+
+impl ColorManagement {
+    pub fn new(working_space: ColorSpace, display: DisplayTransform, dither: bool) -> Self {
+        ColorManagement {
+            working_space,
+            display,
+            dither,
+        }
+    }
+
+    pub fn encode<T: Rng>(&self, rng: &mut T, color: &Vec3) -> [u8; 3] {
+        let display_linear = self.working_space.convert(color, ColorSpace::LinearSrgb);
+        let transfer = self.display.transfer_function();
+
+        [display_linear.x, display_linear.y, display_linear.z].map(|channel| {
+            let encoded = transfer.encode(channel.clamp(0.0, 1.0)) * 255.0;
+            let noise = if self.dither {
+                rng.gen::<f32>() - rng.gen::<f32>()
+            } else {
+                0.0
+            };
+            (encoded + noise).round().clamp(0.0, 255.0) as u8
+        })
+    }
+}
+
+impl Default for ColorManagement {
+    fn default() -> Self {
+        ColorManagement::new(ColorSpace::LinearSrgb, DisplayTransform::Srgb, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+
+    use super::*;
+
+    #[test]
+    fn rounds_to_nearest() {
+        let mut rng = StepRng::new(0, 0);
+        let color_management = ColorManagement::default();
+
+        // sRGB(0.5) = 0.7354, which is 187.52 in 8 bits.
+        let given = color_management.encode(&mut rng, &Vec3::repeat(0.5));
+        assert_eq!(given, [188, 188, 188]);
+    }
+
+    #[test]
+    fn clips_out_of_range() {
+        let mut rng = StepRng::new(0, 0);
+        let color_management = ColorManagement::default();
+
+        let given = color_management.encode(&mut rng, &Vec3::new(-1.0, 0.0, 4.0));
+        assert_eq!(given, [0, 0, 255]);
+    }
+
+    #[test]
+    fn dither_stays_within_one_step() {
+        let mut rng = rand::thread_rng();
+        let color_management =
+            ColorManagement::new(ColorSpace::LinearSrgb, DisplayTransform::Srgb, true);
+
+        for _ in 0..100 {
+            let [r, _, _] = color_management.encode(&mut rng, &Vec3::repeat(0.5));
+            assert!((187..=189).contains(&r), "given {}", r);
+        }
+    }
+}
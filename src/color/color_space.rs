@@ -0,0 +1,79 @@
+use glm::Mat3;
+use glm::Vec3;
+
+/// Linear working spaces a scene can be authored and rendered in.
+/// Linear sRGB shares its primaries and white point with Rec.709.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSpace {
+    LinearSrgb,
+    /// ACES AP1 primaries with a D60 white point.
+    AcesCg,
+}
+
+impl ColorSpace {
+    #[rustfmt::skip]
+    // Bradford adapted from D60 to D65.
+    fn matrix_to_linear_srgb(&self) -> Mat3 {
+        match self {
+            ColorSpace::LinearSrgb => Mat3::identity(),
+            ColorSpace::AcesCg => Mat3::new(
+                 1.705051, -0.621792, -0.083259,
+                -0.130256,  1.140805, -0.010548,
+                -0.024003, -0.128969,  1.152972,
+            ),
+        }
+    }
+
+    #[rustfmt::skip]
+    fn matrix_from_linear_srgb(&self) -> Mat3 {
+        match self {
+            ColorSpace::LinearSrgb => Mat3::identity(),
+            ColorSpace::AcesCg => Mat3::new(
+                0.613097, 0.339523, 0.047379,
+                0.070194, 0.916354, 0.013452,
+                0.020616, 0.109570, 0.869815,
+            ),
+        }
+    }
+
+    pub fn convert(&self, color: &Vec3, to: ColorSpace) -> Vec3 {
+        if *self == to {
+            *color
+        } else {
+            to.matrix_from_linear_srgb() * self.matrix_to_linear_srgb() * color
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn white_is_preserved() {
+        let white = Vec3::repeat(1.0);
+        let given = ColorSpace::AcesCg.convert(&white, ColorSpace::LinearSrgb);
+
+        assert!(
+            glm::equal_eps(&white, &given, 1e-3).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            white,
+            given
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let color = Vec3::new(0.2, 0.5, 0.8);
+        let aces = ColorSpace::LinearSrgb.convert(&color, ColorSpace::AcesCg);
+        let given = ColorSpace::AcesCg.convert(&aces, ColorSpace::LinearSrgb);
+
+        assert!(
+            glm::equal_eps(&color, &given, 1e-4).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            color,
+            given
+        )
+    }
+}
@@ -0,0 +1,3 @@
+pub mod color_management;
+pub mod color_space;
+pub mod transfer;
@@ -0,0 +1,103 @@
+/// Transfer functions map linear light to the non-linear signal a display expects (encode, the OETF)
+/// and back again (decode).
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferFunction {
+    Linear,
+    /// IEC 61966-2-1 piecewise sRGB curve.
+    Srgb,
+    /// ITU-R BT.709 camera curve.
+    Rec709,
+    /// Pure power law, e.g. 2.4 for a BT.1886 display.
+    Gamma(f32),
+}
+
+impl TransferFunction {
+    pub fn encode(&self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            TransferFunction::Linear => x,
+            TransferFunction::Srgb => {
+                if x <= 0.0031308 {
+                    12.92 * x
+                } else {
+                    1.055 * x.powf(1.0 / 2.4) - 0.055
+                }
+            }
+            TransferFunction::Rec709 => {
+                if x < 0.018 {
+                    4.5 * x
+                } else {
+                    1.099 * x.powf(0.45) - 0.099
+                }
+            }
+            TransferFunction::Gamma(gamma) => x.powf(1.0 / gamma),
+        }
+    }
+
+    pub fn decode(&self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            TransferFunction::Linear => x,
+            TransferFunction::Srgb => {
+                if x <= 0.04045 {
+                    x / 12.92
+                } else {
+                    ((x + 0.055) / 1.055).powf(2.4)
+                }
+            }
+            TransferFunction::Rec709 => {
+                if x < 0.081 {
+                    x / 4.5
+                } else {
+                    ((x + 0.099) / 1.099).powf(1.0 / 0.45)
+                }
+            }
+            TransferFunction::Gamma(gamma) => x.powf(*gamma),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_endpoints() {
+        assert_eq!(TransferFunction::Srgb.encode(0.0), 0.0);
+        assert!((TransferFunction::Srgb.encode(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn srgb_linear_segment() {
+        let given = TransferFunction::Srgb.encode(0.001);
+        assert!((given - 0.01292).abs() < 1e-6, "given {}", given);
+    }
+
+    #[test]
+    fn srgb_mid_grey() {
+        let given = TransferFunction::Srgb.encode(0.18);
+        assert!((given - 0.4614).abs() < 1e-3, "given {}", given);
+    }
+
+    #[test]
+    fn round_trip() {
+        for transfer in [
+            TransferFunction::Linear,
+            TransferFunction::Srgb,
+            TransferFunction::Rec709,
+            TransferFunction::Gamma(2.4),
+        ] {
+            for x in [0.0, 0.002, 0.05, 0.18, 0.5, 1.0] {
+                let given = transfer.decode(transfer.encode(x));
+                assert!(
+                    (given - x).abs() < 1e-4,
+                    "{:?}: expected {}, given {}",
+                    transfer,
+                    x,
+                    given
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use glm::Vec3;
+
+/// Scalar field that scales the extinction of a heterogeneous medium.
+pub trait Density: Send + Sync {
+    fn density(&self, p: &Vec3) -> f32;
+
+    /// Upper bound of `density` everywhere, used as the majorant for tracking.
+    fn max_density(&self) -> f32;
+}
@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+pub mod density;
+pub mod noise_density;
+pub mod voxel_grid;
@@ -0,0 +1,38 @@
+use glm::Vec3;
+
+use crate::texture::perlin::Perlin;
+
+use super::density::Density;
+
+/// Billowing cloud density from fractional Brownian motion, in (0..1).
+pub struct NoiseDensity {
+    perlin: Perlin,
+    scale: f32,
+    octaves: u32,
+    /// Fraction of space filled with non-zero density.
+    coverage: f32,
+}
+
+impl NoiseDensity {
+    pub fn new(scale: f32, octaves: u32, coverage: f32, seed: u64) -> Self {
+        NoiseDensity {
+            perlin: Perlin::new(seed),
+            scale,
+            octaves,
+            coverage: coverage.clamp(1e-3, 1.0),
+        }
+    }
+}
+
+impl Density for NoiseDensity {
+    fn density(&self, p: &Vec3) -> f32 {
+        let fbm = self.perlin.fbm(&(p * self.scale), self.octaves, 2.0, 0.5);
+        let value = 0.5 * (1.0 + fbm);
+        let threshold = 1.0 - self.coverage;
+        ((value - threshold) / self.coverage).clamp(0.0, 1.0)
+    }
+
+    fn max_density(&self) -> f32 {
+        1.0
+    }
+}
@@ -0,0 +1,191 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use glm::Vec3;
+
+use super::density::Density;
+
+/// Densities sampled on a regular grid stretched over the box from `min` to `max`, with
+/// trilinear interpolation between voxel centers. The density is zero outside the box.
+pub struct VoxelGrid {
+    dimensions: [usize; 3],
+    /// Voxels with x varying fastest, then y, then z.
+    voxels: Vec<f32>,
+    min: Vec3,
+    max: Vec3,
+    max_density: f32,
+}
+
+impl VoxelGrid {
+    pub fn new(dimensions: [usize; 3], voxels: Vec<f32>, min: Vec3, max: Vec3) -> Self {
+        let count = voxel_count(dimensions)
+            .expect("Grid dimensions must be positive and their product must fit in memory");
+        assert_eq!(
+            voxels.len(),
+            count,
+            "Grid dimensions do not match the number of voxels"
+        );
+
+        let max_density = voxels.iter().fold(0.0_f32, |max, &voxel| max.max(voxel));
+
+        VoxelGrid {
+            dimensions,
+            voxels,
+            min,
+            max,
+            max_density,
+        }
+    }
+
+    /// Loads a raw grid: three little endian `u32` dimensions (x, y, z) followed by one little
+    /// endian `f32` per voxel, x varying fastest.
+    #[allow(dead_code)]
+    pub fn load<P: AsRef<Path>>(path: P, min: Vec3, max: Vec3) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        if bytes.len() < 12 {
+            return Err(invalid("Voxel grid header is truncated"));
+        }
+
+        let mut words = bytes
+            .chunks_exact(4)
+            .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let dimensions = [0; 3].map(|_| u32::from_le_bytes(words.next().unwrap()) as usize);
+        let Some(count) = voxel_count(dimensions) else {
+            return Err(invalid("Voxel grid dimensions are zero or too large"));
+        };
+        let voxels: Vec<f32> = words.map(f32::from_le_bytes).collect();
+
+        if bytes.len() % 4 != 0 || voxels.len() != count {
+            return Err(invalid("Voxel grid size does not match its dimensions"));
+        }
+
+        Ok(VoxelGrid::new(dimensions, voxels, min, max))
+    }
+
+    fn voxel(&self, x: i64, y: i64, z: i64) -> f32 {
+        let [nx, ny, nz] = self.dimensions.map(|n| n as i64);
+        let (x, y, z) = (x.clamp(0, nx - 1), y.clamp(0, ny - 1), z.clamp(0, nz - 1));
+        self.voxels[(x + nx * (y + ny * z)) as usize]
+    }
+}
+
+/// Number of voxels in a grid of `dimensions`, or `None` if it is empty or too large to count.
+fn voxel_count(dimensions: [usize; 3]) -> Option<usize> {
+    if dimensions.contains(&0) {
+        return None;
+    }
+    dimensions
+        .iter()
+        .try_fold(1_usize, |count, &n| count.checked_mul(n))
+}
+
+impl Density for VoxelGrid {
+    fn density(&self, p: &Vec3) -> f32 {
+        let local = (p - self.min).component_div(&(self.max - self.min));
+        if local.iter().any(|&c| !(0.0..=1.0).contains(&c)) {
+            return 0.0;
+        }
+
+        // Voxel centers sit at half integer coordinates.
+        let grid = Vec3::from_fn(|i, _| local[i] * self.dimensions[i] as f32 - 0.5);
+        let base = grid.map(|c| c.floor());
+        let fraction = grid - base;
+        let [x, y, z] = [0, 1, 2].map(|i| base[i] as i64);
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let plane = |z: i64| {
+            let bottom = lerp(self.voxel(x, y, z), self.voxel(x + 1, y, z), fraction.x);
+            let top = lerp(
+                self.voxel(x, y + 1, z),
+                self.voxel(x + 1, y + 1, z),
+                fraction.x,
+            );
+            lerp(bottom, top, fraction.y)
+        };
+        lerp(plane(z), plane(z + 1), fraction.z)
+    }
+
+    fn max_density(&self) -> f32 {
+        self.max_density
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp() -> VoxelGrid {
+        // 2x1x1 grid from density 0 to 1 along x, over the unit cube.
+        VoxelGrid::new([2, 1, 1], vec![0.0, 1.0], Vec3::zeros(), Vec3::repeat(1.0))
+    }
+
+    #[test]
+    fn interpolates_between_voxel_centers() {
+        let grid = ramp();
+        let cases = [(0.1, 0.0), (0.25, 0.0), (0.5, 0.5), (0.75, 1.0), (0.9, 1.0)];
+
+        for (x, expected) in cases {
+            let given = grid.density(&Vec3::new(x, 0.5, 0.5));
+            assert!(
+                (given - expected).abs() < 1e-5,
+                "expected {}, given {}",
+                expected,
+                given
+            );
+        }
+
+        assert_eq!(grid.density(&Vec3::new(1.5, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.max_density(), 1.0);
+    }
+
+    #[test]
+    fn loads_raw_files() {
+        let path = std::env::temp_dir().join("voxel_grid_loads_raw_files.raw");
+        let words: [[u8; 4]; 5] = [
+            2_u32.to_le_bytes(),
+            1_u32.to_le_bytes(),
+            1_u32.to_le_bytes(),
+            0.0_f32.to_le_bytes(),
+            1.0_f32.to_le_bytes(),
+        ];
+        fs::write(&path, words.concat()).unwrap();
+
+        let grid = VoxelGrid::load(&path, Vec3::zeros(), Vec3::repeat(1.0)).unwrap();
+        fs::write(&path, &words.concat()[..16]).unwrap();
+        let truncated = VoxelGrid::load(&path, Vec3::zeros(), Vec3::repeat(1.0));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(grid.voxels, ramp().voxels);
+        assert_eq!(grid.dimensions, [2, 1, 1]);
+        assert!(truncated.is_err());
+    }
+    #[test]
+    fn rejects_empty_and_oversized_headers() {
+        let path = std::env::temp_dir().join(format!(
+            "voxel_grid_rejects_headers_{}.raw",
+            std::process::id()
+        ));
+        let headers = [[2, 0, 1], [u32::MAX, u32::MAX, u32::MAX]];
+
+        let results = headers.map(|header| {
+            let bytes: Vec<u8> = header.iter().flat_map(|n| n.to_le_bytes()).collect();
+            fs::write(&path, bytes).unwrap();
+            VoxelGrid::load(&path, Vec3::zeros(), Vec3::repeat(1.0))
+        });
+        fs::remove_file(&path).unwrap();
+
+        for result in results {
+            let error = result.err().expect("The header should be rejected");
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must be positive")]
+    fn new_rejects_empty_dimensions() {
+        VoxelGrid::new([0, 1, 1], vec![], Vec3::zeros(), Vec3::repeat(1.0));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+
+/// Bounding volume hierarchy over a set of geometries, split at the median centroid along the
+/// longest axis of every node.
+///
+/// It only accelerates ray queries, so lights to sample still belong in a `World`.
+pub struct Bvh {
+    bounds: Aabb,
+    node: Node,
+}
+
+enum Node {
+    Empty,
+    Leaf(Box<dyn Geometry>),
+    Branch(Box<Bvh>, Box<Bvh>),
+}
+
+impl Bvh {
+    pub fn new(items: Vec<Box<dyn Geometry>>) -> Self {
+        // Bounding boxes can be costly, such as those swept by moving instances, so each is
+        // computed once for the whole build.
+        let items = items
+            .into_iter()
+            .map(|item| {
+                let bounds = item.bounding_box();
+                (item, bounds)
+            })
+            .collect();
+        Bvh::build(items)
+    }
+
+    fn build(mut items: Vec<(Box<dyn Geometry>, Aabb)>) -> Self {
+        let bounds = items
+            .iter()
+            .fold(Aabb::empty(), |bounds, (_, item)| bounds.union(item));
+
+        let node = match items.len() {
+            0 => Node::Empty,
+            1 => Node::Leaf(items.pop().unwrap().0),
+            count => {
+                let centroids = items.iter().fold(Aabb::empty(), |centroids, (_, bounds)| {
+                    let centroid = bounds.centroid();
+                    centroids.union(&Aabb::new(centroid, centroid))
+                });
+                let axis = centroids.longest_axis();
+                items.sort_by(|(_, a), (_, b)| a.centroid()[axis].total_cmp(&b.centroid()[axis]));
+
+                let right = items.split_off(count / 2);
+                Node::Branch(Box::new(Bvh::build(items)), Box::new(Bvh::build(right)))
+            }
+        };
+
+        Bvh { bounds, node }
+    }
+}
+
+impl Geometry for Bvh {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        if !self.bounds.hit(ray, interval) {
+            return None;
+        }
+
+        match &self.node {
+            Node::Empty => None,
+            Node::Leaf(item) => item.hit(ray, interval),
+            Node::Branch(left, right) => {
+                let left_hit = left.hit(ray, interval);
+                let max = left_hit.as_ref().map_or(interval.max, |left| left.t);
+                right
+                    .hit(ray, &Interval::new(interval.min, max))
+                    .or(left_hit)
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        if !self.bounds.hit(ray, interval) {
+            return false;
+        }
+
+        match &self.node {
+            Node::Empty => false,
+            Node::Leaf(item) => item.occluded(ray, interval),
+            Node::Branch(left, right) => {
+                left.occluded(ray, interval) || right.occluded(ray, interval)
+            }
+        }
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        if !self.bounds.hit(ray, interval) {
+            return 1.0;
+        }
+
+        match &self.node {
+            Node::Empty => 1.0,
+            Node::Leaf(item) => item.transmittance(ray, interval),
+            Node::Branch(left, right) => {
+                let transmittance = left.transmittance(ray, interval);
+                if transmittance == 0.0 {
+                    0.0
+                } else {
+                    transmittance * right.transmittance(ray, interval)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::geometry::sphere::Sphere;
+    use crate::geometry::world::World;
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    fn spheres() -> Vec<Box<dyn Geometry>> {
+        (0..50)
+            .map(|i| {
+                let center = Vec3::new((i % 5) as f32, (i / 5 % 5) as f32, -(i / 25) as f32);
+                Box::new(Sphere::new(
+                    center * 2.0,
+                    0.6,
+                    Lambertian::new(Vec3::repeat(0.5)),
+                )) as Box<dyn Geometry>
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hits_match_world() {
+        let bvh = Bvh::new(spheres());
+        let world = World::new(spheres());
+        let interval = Interval::new(0.001, f32::MAX);
+
+        for i in 0..100 {
+            let target = Vec3::new((i % 10) as f32, (i / 10) as f32, -1.0);
+            let ray = Ray::new(
+                Vec3::new(4.0, 4.0, 20.0),
+                target - Vec3::new(4.0, 4.0, 20.0),
+            );
+            let expected = world.hit(&ray, &interval).map(|hit_record| hit_record.t);
+            let given = bvh.hit(&ray, &interval).map(|hit_record| hit_record.t);
+
+            assert_eq!(expected, given, "ray towards {:?}", target);
+            assert_eq!(
+                world.occluded(&ray, &interval),
+                bvh.occluded(&ray, &interval)
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::material::material::Material;
+use crate::math::aabb::Aabb;
+use crate::math::interval::{self, Interval};
+use crate::ray::ray::Ray;
+
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+
+/// Range of `ray` parameters inside the convex `boundary`, clipped to `interval`.
+pub fn boundary_span<G: Geometry>(
+    boundary: &G,
+    ray: &Ray,
+    interval: &Interval,
+) -> Option<(f32, f32)> {
+    let entry = boundary.hit(ray, &interval::INFINITY)?.t;
+    let exit = boundary
+        .hit(ray, &Interval::new(entry + 0.0001, f32::INFINITY))?
+        .t;
+
+    let t_min = entry.max(interval.min).max(0.0);
+    let t_max = exit.min(interval.max);
+    (t_min < t_max).then_some((t_min, t_max))
+}
+
+/// Participating medium of uniform `density` filling a closed, convex `boundary`.
+///
+/// Rays pass through the boundary itself and are instead scattered at an exponentially
+/// distributed distance inside it, by the `phase` function material.
+pub struct ConstantMedium<G: Geometry, T: Material> {
+    boundary: G,
+    /// Extinction coefficient per unit length.
+    density: f32,
+    phase: T,
+}
+
+impl<G: Geometry, T: Material> ConstantMedium<G, T> {
+    pub fn new(boundary: G, density: f32, phase: T) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            phase,
+        }
+    }
+}
+
+impl<G: Geometry, T: Material + Sync> Geometry for ConstantMedium<G, T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        let (t_min, t_max) = boundary_span(&self.boundary, ray, interval)?;
+        let mut rng = ThreadRng::default();
+
+        let ray_length = glm::length(&ray.direction);
+        let distance_inside = (t_max - t_min) * ray_length;
+        let hit_distance = -(1.0 - rng.gen::<f32>()).ln() / self.density;
+
+        if hit_distance > distance_inside {
+            return None;
+        }
+
+        let t = t_min + hit_distance / ray_length;
+        Some(HitRecord::volume(t, ray.at(t), &self.phase))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        match boundary_span(&self.boundary, ray, interval) {
+            Some((t_min, t_max)) => {
+                let distance_inside = (t_max - t_min) * glm::length(&ray.direction);
+                (-self.density * distance_inside).exp()
+            }
+            None => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::geometry::sphere::Sphere;
+    use crate::material::isotropic::Isotropic;
+
+    use super::*;
+
+    #[test]
+    fn transmittance_follows_beer_lambert() {
+        let boundary = Sphere::new(Vec3::zeros(), 1.0, Isotropic::new(Vec3::repeat(1.0)));
+        let medium = ConstantMedium::new(boundary, 0.5, Isotropic::new(Vec3::repeat(1.0)));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 2.0));
+        let interval = Interval::new(0.001, f32::MAX);
+        let samples = 20_000;
+
+        let passed = (0..samples)
+            .filter(|_| !medium.occluded(&ray, &interval))
+            .count();
+        let given = passed as f32 / samples as f32;
+        let expected = (-0.5_f32 * 2.0).exp();
+
+        assert!(
+            (given - expected).abs() < 0.02,
+            "expected {}, given {}",
+            expected,
+            given
+        );
+
+        let given = medium.transmittance(&ray, &interval);
+        assert!(
+            (given - expected).abs() < 1e-5,
+            "expected {}, given {}",
+            expected,
+            given
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+
+use crate::material::material::Material;
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+use super::quad::Quad;
+
+/// Axis aligned box made of six outward facing quads that share one material.
+pub struct Cuboid<T: Material> {
+    sides: [Quad<Arc<T>>; 6],
+}
+
+impl<T: Material> Cuboid<T> {
+    /// Box spanned by the opposite corners `a` and `b`.
+    pub fn new(a: Vec3, b: Vec3, material: T) -> Self {
+        let min = glm::min2(&a, &b);
+        let max = glm::max2(&a, &b);
+        let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+        let material = Arc::new(material);
+
+        Cuboid {
+            sides: [
+                // Front, right, back, left, top and bottom.
+                Quad::new(Vec3::new(min.x, min.y, max.z), dx, dy, material.clone()),
+                Quad::new(Vec3::new(max.x, min.y, max.z), -dz, dy, material.clone()),
+                Quad::new(Vec3::new(max.x, min.y, min.z), -dx, dy, material.clone()),
+                Quad::new(Vec3::new(min.x, min.y, min.z), dz, dy, material.clone()),
+                Quad::new(Vec3::new(min.x, max.y, max.z), dx, -dz, material.clone()),
+                Quad::new(Vec3::new(min.x, min.y, min.z), dx, dz, material),
+            ],
+        }
+    }
+}
+
+impl<T: Material + Send + Sync> Geometry for Cuboid<T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        self.sides.iter().fold(None, |closest_hit, side| {
+            let max = closest_hit
+                .as_ref()
+                .map_or(interval.max, |closest| closest.t);
+            side.hit(ray, &Interval::new(interval.min, max))
+                .or(closest_hit)
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sides.iter().fold(Aabb::empty(), |bounds, side| {
+            bounds.union(&side.bounding_box())
+        })
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.sides.iter().any(|side| side.occluded(ray, interval))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn normals_face_outwards() {
+        let cuboid = Cuboid::new(
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(-1.0, -1.0, -1.0),
+            Lambertian::new(Vec3::repeat(0.5)),
+        );
+        let interval = Interval::new(0.001, f32::MAX);
+
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut origin = Vec3::zeros();
+                origin[axis] = 3.0 * sign;
+                let ray = Ray::new(origin, -origin.normalize());
+
+                let hit_record = cuboid.hit(&ray, &interval).unwrap();
+                let expected = origin.normalize();
+                assert!(hit_record.front_face);
+                assert!((hit_record.t - 2.0).abs() < 1e-4);
+                assert!(
+                    glm::equal_eps(&expected, &hit_record.normal, 1e-5)
+                        .iter()
+                        .all(|&x| x),
+                    "expected {:?}, given {:?}",
+                    expected,
+                    hit_record.normal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cannot_be_sampled_as_light() {
+        let cuboid = Cuboid::new(
+            Vec3::zeros(),
+            Vec3::repeat(1.0),
+            Lambertian::new(Vec3::repeat(0.5)),
+        );
+        assert!(cuboid.random(&Vec3::repeat(3.0)).is_none());
+    }
+}
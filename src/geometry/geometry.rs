@@ -1,7 +1,67 @@
-use crate::ray::ray::Ray;
-use crate::math::interval::Interval;
+use std::sync::Arc;
+
 use super::hit_record::HitRecord;
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+use glm::Vec3;
 
 pub trait Geometry: Sync {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord>;
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>>;
+
+    /// Box enclosing everything `hit` can return, for acceleration structures.
+    fn bounding_box(&self) -> Aabb;
+
+    /// Any-hit query for shadow rays. Unlike `hit` it may stop at the first intersection found.
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.hit(ray, interval).is_some()
+    }
+
+    /// Fraction of light that makes it along `ray` through `interval`. Surfaces either block
+    /// it or not, participating media let part of it through.
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        if self.occluded(ray, interval) {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Solid angle density of sampling `direction` from `origin` towards this geometry.
+    fn pdf_value(&self, _origin: &Vec3, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    /// Direction from `origin` towards a random point on this geometry, for light sampling.
+    /// `None` for geometries without a `pdf_value`, whose light is only found by chance.
+    fn random(&self, _origin: &Vec3) -> Option<Vec3> {
+        None
+    }
+}
+
+/// Lets instances share one geometry through `Transformed`.
+impl<T: Geometry + Send + ?Sized> Geometry for Arc<T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        (**self).occluded(ray, interval)
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        (**self).transmittance(ray, interval)
+    }
+
+    fn pdf_value(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Vec3) -> Option<Vec3> {
+        (**self).random(origin)
+    }
 }
@@ -0,0 +1,150 @@
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::density::density::Density;
+use crate::material::material::Material;
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::constant_medium::boundary_span;
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+
+/// Participating medium whose extinction varies as `extinction * density(p)` inside a closed,
+/// convex `boundary`.
+///
+/// Free flights are sampled with delta tracking and shadow rays estimate transmittance with
+/// ratio tracking, both against the majorant `extinction * density.max_density()`.
+pub struct HeterogeneousMedium<G: Geometry, D: Density, T: Material> {
+    boundary: G,
+    density: D,
+    /// Extinction coefficient per unit length at a density of one.
+    extinction: f32,
+    phase: T,
+}
+
+impl<G: Geometry, D: Density, T: Material> HeterogeneousMedium<G, D, T> {
+    pub fn new(boundary: G, density: D, extinction: f32, phase: T) -> Self {
+        HeterogeneousMedium {
+            boundary,
+            density,
+            extinction,
+            phase,
+        }
+    }
+
+    /// Calls `collision` with the ray parameter of every tentative collision inside the
+    /// boundary and the probability that it is real, until it returns false or the ray leaves
+    /// the medium.
+    fn track<F: FnMut(f32, f32) -> bool>(&self, ray: &Ray, interval: &Interval, mut collision: F) {
+        let majorant = self.extinction * self.density.max_density();
+        let (t_min, t_max) = match boundary_span(&self.boundary, ray, interval) {
+            Some(span) if majorant > 0.0 => span,
+            _ => return,
+        };
+
+        let mut rng = ThreadRng::default();
+        let step = majorant * glm::length(&ray.direction);
+        let mut t = t_min;
+
+        loop {
+            t -= (1.0 - rng.gen::<f32>()).ln() / step;
+            if t >= t_max {
+                return;
+            }
+
+            let extinction = self.extinction * self.density.density(&ray.at(t));
+            if !collision(t, extinction / majorant) {
+                return;
+            }
+        }
+    }
+}
+
+impl<G: Geometry, D: Density, T: Material + Sync> Geometry for HeterogeneousMedium<G, D, T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        let mut rng = ThreadRng::default();
+        let mut scattered = None;
+
+        // Delta tracking: a tentative collision is real with probability extinction / majorant.
+        self.track(ray, interval, |t, real| {
+            if rng.gen::<f32>() < real {
+                scattered = Some(t);
+            }
+            scattered.is_none()
+        });
+
+        let t = scattered?;
+        Some(HitRecord::volume(t, ray.at(t), &self.phase))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        let mut transmittance = 1.0;
+
+        // Ratio tracking: every tentative collision removes the real fraction of the light.
+        self.track(ray, interval, |_, real| {
+            transmittance *= 1.0 - real;
+            transmittance > 0.0
+        });
+        transmittance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::density::voxel_grid::VoxelGrid;
+    use crate::geometry::cuboid::Cuboid;
+    use crate::material::isotropic::Isotropic;
+
+    use super::*;
+
+    #[test]
+    fn matches_constant_medium_for_uniform_grid() {
+        let grid = VoxelGrid::new(
+            [2, 2, 2],
+            vec![0.5; 8],
+            Vec3::repeat(-1.0),
+            Vec3::repeat(1.0),
+        );
+        let boundary = Cuboid::new(
+            Vec3::repeat(-1.0),
+            Vec3::repeat(1.0),
+            Isotropic::new(Vec3::zeros()),
+        );
+        let medium = HeterogeneousMedium::new(boundary, grid, 2.0, Isotropic::new(Vec3::zeros()));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let interval = Interval::new(0.001, f32::MAX);
+        let samples = 20_000;
+        // Density 0.5 times extinction 2 over a path length of 2.
+        let expected = (-2.0_f32).exp();
+
+        let passed = (0..samples)
+            .filter(|_| medium.hit(&ray, &interval).is_none())
+            .count() as f32
+            / samples as f32;
+        let ratio = (0..samples)
+            .map(|_| medium.transmittance(&ray, &interval))
+            .sum::<f32>()
+            / samples as f32;
+
+        assert!(
+            (passed - expected).abs() < 0.02,
+            "expected {}, given {}",
+            expected,
+            passed
+        );
+        assert!(
+            (ratio - expected).abs() < 0.02,
+            "expected {}, given {}",
+            expected,
+            ratio
+        );
+    }
+}
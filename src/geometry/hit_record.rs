@@ -1,12 +1,24 @@
+use glm::Vec2;
 use glm::Vec3;
 
 use crate::material::material::Material;
+use crate::math::onb::Onb;
 
 pub struct HitRecord<'a> {
     pub p: Vec3,
+    /// Hit point relative to the primitive's own frame, for solid textures that stick to it.
+    pub object_p: Vec3,
     pub normal: Vec3,
+    /// Direction in which `u` grows along the surface, orienting anisotropic materials.
+    /// Arbitrary around the normal for surfaces without one.
+    pub tangent: Vec3,
     pub t: f32,
     pub front_face: bool,
+    /// Surface coordinates in (0..1), used for texture lookups.
+    pub u: f32,
+    pub v: f32,
+    /// Width of the ray cone at the hit along `u` and `v`, in uv units. Zero when unknown.
+    pub footprint: Vec2,
     pub material: &'a dyn Material,
 }
 
@@ -16,14 +28,27 @@ impl<'a> HitRecord<'a> {
         p: Vec3,
         normal: Vec3,
         front_face: bool,
+        u: f32,
+        v: f32,
         material: &'a dyn Material,
     ) -> Self {
         HitRecord {
             t,
             p,
+            object_p: p,
             normal,
+            tangent: Onb::new(&normal).u,
             front_face,
+            u,
+            v,
+            footprint: Vec2::zeros(),
             material,
         }
     }
+
+    /// Scattering event inside a participating medium. It has no surface, so the normal and
+    /// uvs are arbitrary.
+    pub fn volume(t: f32, p: Vec3, material: &'a dyn Material) -> Self {
+        HitRecord::new(t, p, Vec3::x(), true, 0.0, 0.0, material)
+    }
 }
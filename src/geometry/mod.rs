@@ -1,4 +1,11 @@
+pub mod bvh;
+pub mod constant_medium;
+pub mod cuboid;
+#[allow(clippy::module_inception)]
 pub mod geometry;
+pub mod heterogeneous_medium;
 pub mod hit_record;
+pub mod quad;
 pub mod sphere;
+pub mod transformed;
 pub mod world;
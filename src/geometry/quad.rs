@@ -0,0 +1,155 @@
+use glm::Vec2;
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::material::material::Material;
+use crate::math::aabb::Aabb;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+
+const EPSILON: f32 = 1e-8;
+
+/// Parallelogram spanned by the edges `u` and `v` from the corner `q`.
+pub struct Quad<T: Material> {
+    pub q: Vec3,
+    pub u: Vec3,
+    pub v: Vec3,
+    w: Vec3,
+    normal: Vec3,
+    d: f32,
+    area: f32,
+    material: T,
+}
+
+impl<T: Material> Quad<T> {
+    pub fn new(q: Vec3, u: Vec3, v: Vec3, material: T) -> Self {
+        let n = glm::cross(&u, &v);
+        let normal = n.normalize();
+        let d = glm::dot(&normal, &q);
+        let w = n / glm::dot(&n, &n);
+        let area = glm::length(&n);
+
+        Quad {
+            q,
+            u,
+            v,
+            w,
+            normal,
+            d,
+            area,
+            material,
+        }
+    }
+
+    /// Distance to the intersection within `interval` and its planar coordinates along the edges.
+    fn intersect(&self, ray: &Ray, interval: &Interval) -> Option<(f32, f32, f32)> {
+        let denominator = glm::dot(&self.normal, &ray.direction);
+
+        if denominator.abs() < EPSILON {
+            return None;
+        }
+
+        let t = (self.d - glm::dot(&self.normal, &ray.origin)) / denominator;
+        if !interval.surrounds(t) {
+            return None;
+        }
+
+        // Planar coordinates of the intersection relative to the edges.
+        let planar = ray.at(t) - self.q;
+        let alpha = glm::dot(&self.w, &glm::cross(&planar, &self.v));
+        let beta = glm::dot(&self.w, &glm::cross(&self.u, &planar));
+        let unit = Interval::new(0.0, 1.0);
+
+        if !unit.contains(alpha) || !unit.contains(beta) {
+            return None;
+        }
+
+        Some((t, alpha, beta))
+    }
+}
+
+impl<T: Material + Sync> Geometry for Quad<T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        let (t, u, v) = self.intersect(ray, interval)?;
+        let p = ray.at(t);
+        let front_face = glm::dot(&ray.direction, &self.normal) < 0.0;
+        let normal = if front_face {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        let mut hit_record = HitRecord::new(t, p, normal, front_face, u, v, &self.material);
+        hit_record.object_p = p - self.q;
+        hit_record.tangent = self.u.normalize();
+        let width = ray.width_at(t);
+        hit_record.footprint = Vec2::new(width / self.u.norm(), width / self.v.norm());
+
+        Some(hit_record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal = Aabb::new(self.q, self.q + self.u + self.v);
+        let other_diagonal = Aabb::new(self.q + self.u, self.q + self.v);
+        diagonal.union(&other_diagonal).pad(1e-4)
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.intersect(ray, interval).is_some()
+    }
+
+    fn pdf_value(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction);
+
+        match self.hit(&ray, &Interval::new(0.001, f32::MAX)) {
+            Some(hit_record) => {
+                let distance_squared = hit_record.t.powi(2) * glm::length2(direction);
+                let cosine = glm::dot(direction, &self.normal).abs() / glm::length(direction);
+                distance_squared / (cosine * self.area)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: &Vec3) -> Option<Vec3> {
+        let mut rng = ThreadRng::default();
+        let p = self.q + rng.gen::<f32>() * self.u + rng.gen::<f32>() * self.v;
+        Some(p - origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn footprint_follows_each_edge() {
+        let quad = Quad::new(
+            Vec3::new(-2.0, -0.5, -1.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Vec3::zeros()),
+        );
+        let mut ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+        ray.spread = 0.1;
+        ray.width = 0.1;
+
+        // The cone is 0.2 wide at the quad, a twentieth of u and a fifth of v.
+        let hit_record = quad.hit(&ray, &Interval::new(0.001, f32::MAX)).unwrap();
+        let expected = Vec2::new(0.05, 0.2);
+        assert!(
+            glm::equal_eps(&expected, &hit_record.footprint, 1e-6)
+                .iter()
+                .all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            hit_record.footprint
+        );
+    }
+}
@@ -1,8 +1,15 @@
+use std::f32::consts::PI;
+
+use glm::Vec2;
 use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
 
 use crate::ray::ray::Ray;
 use crate::material::material::Material;
+use crate::math::aabb::Aabb;
 use crate::math::interval::Interval;
+use crate::math::onb::Onb;
 
 use super::geometry::Geometry;
 use super::hit_record::HitRecord;
@@ -10,6 +17,8 @@ use super::hit_record::HitRecord;
 pub struct Sphere<T: Material> {
     pub center: Vec3,
     pub radius: f32,
+    /// Distance the center travels from time 0 to time 1.
+    motion: Vec3,
     material: T,
 }
 
@@ -18,14 +27,37 @@ impl<T: Material> Sphere<T> {
         Sphere {
             center,
             radius,
+            motion: Vec3::zeros(),
             material,
         }
     }
-}
 
-impl<T: Material + Sync> Geometry for Sphere<T> {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord> {
-        let oc = self.center - ray.origin;
+    /// Sphere moving linearly from `center0` at time 0 to `center1` at time 1. Light sampling
+    /// only sees it at `center0`.
+    pub fn moving(center0: Vec3, center1: Vec3, radius: f32, material: T) -> Self {
+        Sphere {
+            center: center0,
+            radius,
+            motion: center1 - center0,
+            material,
+        }
+    }
+
+    fn center(&self, time: f32) -> Vec3 {
+        self.center + time * self.motion
+    }
+
+    /// Maps a point on the unit sphere to longitude `u` and latitude `v`, both in (0..1).
+    /// `v` runs from the south pole (y = -1) to the north pole (y = 1).
+    fn uv(p: &Vec3) -> (f32, f32) {
+        let theta = (-p.y).acos();
+        let phi = (-p.z).atan2(p.x) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
+
+    /// Distance to the nearest intersection within `interval`.
+    fn root(&self, ray: &Ray, interval: &Interval) -> Option<f32> {
+        let oc = self.center(ray.time) - ray.origin;
         let a = glm::length2(&ray.direction);
         let h = glm::dot(&ray.direction, &oc);
         let c = glm::length2(&oc) - self.radius.powi(2);
@@ -45,18 +77,159 @@ impl<T: Material + Sync> Geometry for Sphere<T> {
             }
         }
 
-        let t = root;
+        Some(root)
+    }
+}
+
+impl<T: Material + Sync> Geometry for Sphere<T> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        let t = self.root(ray, interval)?;
         let p = ray.at(t);
-        let outward_normal = (p - self.center) / self.radius;
+        let center = self.center(ray.time);
+        let outward_normal = (p - center) / self.radius;
         let front_face = glm::dot(&ray.direction, &outward_normal) < 0.0;
         let normal = if front_face { outward_normal } else { -outward_normal };
+        let (u, v) = Sphere::<T>::uv(&outward_normal);
+
+        let mut hit_record = HitRecord::new(t, p, normal, front_face, u, v, &self.material);
+        hit_record.object_p = p - center;
+        // Lines of latitude, which vanish at the poles.
+        let tangent = glm::cross(&Vec3::y(), &outward_normal);
+        if glm::length2(&tangent) > 1e-12 {
+            hit_record.tangent = tangent.normalize();
+        }
+        // A unit step in u spans the circumference at the equator, and in v half of it.
+        let width = ray.width_at(t) / (2.0 * PI * self.radius);
+        hit_record.footprint = Vec2::new(width, 2.0 * width);
+
+        Some(hit_record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let radius = Vec3::repeat(self.radius);
+        let start = Aabb::new(self.center - radius, self.center + radius);
+        let end = Aabb::new(self.center(1.0) - radius, self.center(1.0) + radius);
+        start.union(&end)
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.root(ray, interval).is_some()
+    }
+
+    fn pdf_value(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        let distance_squared = glm::length2(&(self.center - origin));
+        let ray = Ray::new(*origin, *direction);
+
+        if distance_squared <= self.radius.powi(2)
+            || self.hit(&ray, &Interval::new(0.001, f32::MAX)).is_none()
+        {
+            return 0.0;
+        }
+
+        let cos_theta_max = (1.0 - self.radius.powi(2) / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    fn random(&self, origin: &Vec3) -> Option<Vec3> {
+        // Uniformly samples the cone of directions subtended by the sphere.
+        let mut rng = ThreadRng::default();
+        let direction = self.center - origin;
+        let distance_squared = glm::length2(&direction);
+        let cos_theta_max = (1.0 - self.radius.powi(2) / distance_squared).max(0.0).sqrt();
+
+        let r1: f32 = rng.gen();
+        let r2: f32 = rng.gen();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z.powi(2)).sqrt();
+        let local = Vec3::new(phi.cos() * sin_theta, phi.sin() * sin_theta, z);
+
+        Some(Onb::new(&direction).local_to_world(&local))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn occluded_matches_hit() {
+        let sphere = Sphere::new(Vec3::new(0.0, 0.0, -2.0), 0.5, Lambertian::new(Vec3::zeros()));
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+
+        for max in [1.0, 1.6, 2.0, 3.0] {
+            let interval = Interval::new(0.001, max);
+            assert_eq!(
+                sphere.occluded(&ray, &interval),
+                sphere.hit(&ray, &interval).is_some(),
+                "interval up to {}",
+                max
+            );
+        }
+    }
+
+    #[test]
+    fn uv_at_poles_and_equator() {
+        let cases = [
+            (Vec3::new(0.0, -1.0, 0.0), 0.0),
+            (Vec3::new(0.0, 1.0, 0.0), 1.0),
+            (Vec3::new(-1.0, 0.0, 0.0), 0.5),
+        ];
+
+        for (p, expected) in cases {
+            let (_, given) = Sphere::<Lambertian>::uv(&p);
+            assert!((given - expected).abs() < 1e-6, "expected {}, given {}", expected, given);
+        }
+
+        let (given, _) = Sphere::<Lambertian>::uv(&Vec3::new(1.0, 0.0, 0.0));
+        assert!((given - 0.5).abs() < 1e-6, "given {}", given);
+    }
+
+    #[test]
+    fn tangent_follows_u() {
+        let sphere = Sphere::new(Vec3::zeros(), 1.0, Lambertian::new(Vec3::zeros()));
+        let interval = Interval::new(0.001, f32::MAX);
+        let tangent_at = |z: f32| {
+            let ray = Ray::new(Vec3::new(5.0, 0.3, z), Vec3::new(-1.0, 0.0, 0.0));
+            let hit_record = sphere.hit(&ray, &interval).unwrap();
+            let (u, _) = Sphere::<Lambertian>::uv(&(hit_record.p + 1e-3 * hit_record.tangent));
+            assert!(u > hit_record.u, "expected u > {}, given {}", hit_record.u, u);
+            hit_record.tangent
+        };
+
+        // Normals on either side of z = 0, where a basis built from the normal alone flips.
+        let (above, below) = (tangent_at(0.01), tangent_at(-0.01));
+        assert!(glm::dot(&above, &below) > 0.99, "given {:?}, {:?}", above, below);
+    }
+
+    #[test]
+    fn occluded_from_inside() {
+        let sphere = Sphere::new(Vec3::zeros(), 1.0, Lambertian::new(Vec3::zeros()));
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(1.0, 0.0, 0.0));
+
+        assert!(sphere.occluded(&ray, &Interval::new(0.001, 2.0)));
+        assert!(!sphere.occluded(&ray, &Interval::new(0.001, 0.5)));
+    }
+
+    #[test]
+    fn moving_sphere_follows_time() {
+        let sphere = Sphere::moving(
+            Vec3::zeros(),
+            Vec3::new(2.0, 0.0, 0.0),
+            1.0,
+            Lambertian::new(Vec3::zeros()),
+        );
+        let interval = Interval::new(0.001, f32::MAX);
+        let mut ray = Ray::new(Vec3::new(2.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+
+        assert!(sphere.hit(&ray, &interval).is_none());
 
-        Some(HitRecord::new(
-            t,
-            p,
-            normal,
-            front_face,
-            &self.material,
-        ))
+        ray.time = 1.0;
+        let hit_record = sphere.hit(&ray, &interval).unwrap();
+        assert!((hit_record.t - 4.0).abs() < 1e-5, "given {}", hit_record.t);
+        assert_eq!(hit_record.object_p, Vec3::new(0.0, 0.0, 1.0));
     }
 }
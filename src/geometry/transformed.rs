@@ -0,0 +1,282 @@
+use glm::Mat3;
+use glm::Mat4;
+use glm::Vec3;
+use glm::Vec4;
+
+use crate::material::material::Material;
+use crate::math::aabb::Aabb;
+use crate::math::decomposed::Decomposed;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::geometry::Geometry;
+use super::hit_record::HitRecord;
+
+/// Places `geometry` in the world through an affine `object_to_world` matrix.
+///
+/// Rays are taken into object space rather than normalized, so ray parameters and intervals
+/// carry over unchanged. Wrapping an `Arc<dyn Geometry + Send>` instances one geometry many
+/// times, and `set_material` gives an instance its own material.
+pub struct Transformed<G: Geometry> {
+    geometry: G,
+    object_to_world: Mat4,
+    world_to_object: Mat4,
+    /// Inverse transpose of the linear part, which keeps normals perpendicular to surfaces.
+    normal_to_world: Mat3,
+    /// Transforms at times 0 and 1 of an animated instance.
+    motion: Option<(Decomposed, Decomposed)>,
+    material: Option<Box<dyn Material + Send + Sync>>,
+}
+
+impl<G: Geometry> Transformed<G> {
+    pub fn new(geometry: G, object_to_world: Mat4) -> Self {
+        let (world_to_object, normal_to_world) = inverses(&object_to_world);
+
+        Transformed {
+            geometry,
+            object_to_world,
+            world_to_object,
+            normal_to_world,
+            motion: None,
+            material: None,
+        }
+    }
+
+    /// Animates the instance from its transform at time 0 to `end_object_to_world` at time 1.
+    /// Light sampling only sees it at time 0.
+    pub fn set_motion(&mut self, end_object_to_world: Mat4) {
+        self.motion = Some((
+            Decomposed::new(&self.object_to_world),
+            Decomposed::new(&end_object_to_world),
+        ));
+    }
+
+    /// Replaces the material of every hit on this instance.
+    pub fn set_material<T: Material + Send + Sync + 'static>(&mut self, material: T) {
+        self.material = Some(Box::new(material));
+    }
+
+    /// Object to world, world to object and normal matrices at `time`. Animated instances
+    /// invert their matrix on every call, so callers compute them once per ray.
+    fn matrices_at(&self, time: f32) -> (Mat4, Mat4, Mat3) {
+        match &self.motion {
+            Some((start, end)) => {
+                let object_to_world = start.interpolate(end, time).matrix();
+                let (world_to_object, normal_to_world) = inverses(&object_to_world);
+                (object_to_world, world_to_object, normal_to_world)
+            }
+            None => (
+                self.object_to_world,
+                self.world_to_object,
+                self.normal_to_world,
+            ),
+        }
+    }
+
+    fn to_object(&self, ray: &Ray) -> Ray {
+        let (_, world_to_object, _) = self.matrices_at(ray.time);
+        ray_to_object(ray, &world_to_object)
+    }
+
+    fn point_to_object(&self, p: &Vec3) -> Vec3 {
+        (self.world_to_object * Vec4::new(p.x, p.y, p.z, 1.0)).xyz()
+    }
+}
+
+impl<G: Geometry> Geometry for Transformed<G> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
+        let (object_to_world, world_to_object, normal_to_world) = self.matrices_at(ray.time);
+        let mut hit_record = self
+            .geometry
+            .hit(&ray_to_object(ray, &world_to_object), interval)?;
+
+        // `object_p` is left in the frame of the wrapped geometry.
+        hit_record.p = ray.at(hit_record.t);
+        hit_record.normal = (normal_to_world * hit_record.normal).normalize();
+        let tangent = glm::vec3_to_vec4(&hit_record.tangent);
+        hit_record.tangent = (object_to_world * tangent).xyz().normalize();
+        if let Some(material) = &self.material {
+            hit_record.material = material.as_ref();
+        }
+
+        Some(hit_record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bounds = self.geometry.bounding_box();
+        match &self.motion {
+            // Rotations sweep outside the boxes at either end, so sample the motion in between.
+            Some((start, end)) => (0..=16).fold(Aabb::empty(), |swept, step| {
+                let transform = start.interpolate(end, step as f32 / 16.0).matrix();
+                swept.union(&bounds.transform(&transform))
+            }),
+            None => bounds.transform(&self.object_to_world),
+        }
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.geometry.occluded(&self.to_object(ray), interval)
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        self.geometry.transmittance(&self.to_object(ray), interval)
+    }
+
+    /// Changes the wrapped geometry's density to world space. The linear part `M` of the world
+    /// to object matrix stretches solid angles around a unit direction `w` by
+    /// `|det M| / |M w|^3`, which is one for rigid motions and uniform scaling only.
+    fn pdf_value(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        let linear = glm::mat4_to_mat3(&self.world_to_object);
+        let object_direction = linear * direction.normalize();
+        let jacobian = linear.determinant().abs() / object_direction.norm().powi(3);
+
+        self.geometry
+            .pdf_value(&self.point_to_object(origin), &object_direction)
+            * jacobian
+    }
+
+    fn random(&self, origin: &Vec3) -> Option<Vec3> {
+        let direction = self.geometry.random(&self.point_to_object(origin))?;
+        Some((self.object_to_world * glm::vec3_to_vec4(&direction)).xyz())
+    }
+}
+
+fn ray_to_object(ray: &Ray, world_to_object: &Mat4) -> Ray {
+    let origin = ray.origin;
+    let mut object_ray = Ray::new(
+        (world_to_object * Vec4::new(origin.x, origin.y, origin.z, 1.0)).xyz(),
+        (world_to_object * glm::vec3_to_vec4(&ray.direction)).xyz(),
+    );
+    object_ray.spread = ray.spread;
+    // Scaled like the direction, a step along which spans the same part of the cone.
+    object_ray.width = ray.width * object_ray.direction.norm() / ray.direction.norm();
+    object_ray.time = ray.time;
+    object_ray
+}
+
+/// World to object matrix and normal matrix for `object_to_world`.
+fn inverses(object_to_world: &Mat4) -> (Mat4, Mat3) {
+    let world_to_object = object_to_world
+        .try_inverse()
+        .expect("The object to world matrix is not invertible");
+    let normal_to_world = glm::mat4_to_mat3(&world_to_object).transpose();
+    (world_to_object, normal_to_world)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::geometry::quad::Quad;
+    use crate::geometry::sphere::Sphere;
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn hit_on_translated_and_scaled_sphere() {
+        let sphere = Sphere::new(Vec3::zeros(), 1.0, Lambertian::new(Vec3::repeat(0.5)));
+        let object_to_world =
+            glm::translation(&Vec3::new(0.0, 0.0, -5.0)) * glm::scaling(&Vec3::new(1.0, 1.0, 2.0));
+        let transformed = Transformed::new(sphere, object_to_world);
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+        let hit_record = transformed
+            .hit(&ray, &Interval::new(0.001, f32::MAX))
+            .unwrap();
+
+        let cases = [
+            (Vec3::new(0.0, 0.0, -3.0), hit_record.p),
+            (Vec3::new(0.0, 0.0, 1.0), hit_record.normal),
+            (Vec3::new(0.0, 0.0, 1.0), hit_record.object_p),
+        ];
+        for (expected, given) in cases {
+            assert!(
+                glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                given
+            );
+        }
+        assert!((hit_record.t - 3.0).abs() < 1e-5, "given {}", hit_record.t);
+    }
+
+    #[test]
+    fn instances_share_geometry_with_their_own_materials() {
+        let albedo = Vec3::new(0.2, 0.4, 0.6);
+        let sphere: Arc<dyn Geometry + Send> = Arc::new(Sphere::new(
+            Vec3::zeros(),
+            1.0,
+            Lambertian::new(Vec3::repeat(0.5)),
+        ));
+        let mut instance = Transformed::new(
+            sphere.clone(),
+            glm::rotation(1.0, &Vec3::new(0.0, 1.0, 0.0)),
+        );
+        instance.set_material(Lambertian::new(albedo));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let interval = Interval::new(0.001, f32::MAX);
+
+        let original = sphere.hit(&ray, &interval).unwrap();
+        let instanced = instance.hit(&ray, &interval).unwrap();
+        let normal = instanced.normal;
+        let expected = Lambertian::new(albedo).eval(&ray, &instanced, &normal);
+        let given = instanced.material.eval(&ray, &instanced, &normal);
+
+        assert!((original.t - instanced.t).abs() < 1e-5);
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn pdf_matches_world_space_quad() {
+        let material = Arc::new(Lambertian::new(Vec3::repeat(0.5)));
+        let unit = Quad::new(Vec3::zeros(), Vec3::x(), Vec3::y(), material.clone());
+        let object_to_world = glm::translation(&Vec3::new(0.0, 0.0, -3.0))
+            * glm::rotation(0.3, &Vec3::y())
+            * glm::scaling(&Vec3::new(2.0, 0.5, 1.0));
+        let transformed = Transformed::new(unit, object_to_world);
+        let world = Quad::new(
+            (object_to_world * Vec4::new(0.0, 0.0, 0.0, 1.0)).xyz(),
+            (object_to_world * glm::vec3_to_vec4(&Vec3::x())).xyz(),
+            (object_to_world * glm::vec3_to_vec4(&Vec3::y())).xyz(),
+            material,
+        );
+        let origin = Vec3::new(0.3, 0.1, 0.0);
+
+        for _ in 0..20 {
+            let direction = world.random(&origin).unwrap() * 3.0;
+            let expected = world.pdf_value(&origin, &direction);
+            let given = transformed.pdf_value(&origin, &direction);
+
+            assert!(
+                (given - expected).abs() < 1e-3 * expected,
+                "expected {}, given {}",
+                expected,
+                given
+            );
+        }
+    }
+
+    #[test]
+    fn animated_instance_moves_over_time() {
+        let sphere = Sphere::new(Vec3::zeros(), 1.0, Lambertian::new(Vec3::repeat(0.5)));
+        let mut transformed = Transformed::new(sphere, Mat4::identity());
+        transformed.set_motion(glm::translation(&Vec3::new(4.0, 0.0, 0.0)));
+        let interval = Interval::new(0.001, f32::MAX);
+        let mut ray = Ray::new(Vec3::new(2.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+
+        assert!(transformed.hit(&ray, &interval).is_none());
+
+        ray.time = 0.5;
+        let hit_record = transformed.hit(&ray, &interval).unwrap();
+        assert!((hit_record.t - 4.0).abs() < 1e-5, "given {}", hit_record.t);
+
+        let bounds = transformed.bounding_box();
+        assert_eq!(bounds.min, Vec3::repeat(-1.0));
+        assert_eq!(bounds.max, Vec3::new(5.0, 1.0, 1.0));
+    }
+}
@@ -1,3 +1,8 @@
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::math::aabb::Aabb;
 use crate::math::interval::Interval;
 use crate::ray::ray::Ray;
 
@@ -13,13 +18,17 @@ impl World {
         World { items }
     }
 
-    pub fn add(&mut self, item: Box<dyn Geometry>) -> () {
+    pub fn add(&mut self, item: Box<dyn Geometry>) {
         self.items.push(item);
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 }
 
 impl Geometry for World {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord<'_>> {
         self.items.iter().fold(None, |closest_hit, hittable| {
             if let Some(ref closest) = closest_hit {
                 hittable
@@ -30,4 +39,42 @@ impl Geometry for World {
             }
         })
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.items.iter().fold(Aabb::empty(), |bounds, item| {
+            bounds.union(&item.bounding_box())
+        })
+    }
+
+    fn occluded(&self, ray: &Ray, interval: &Interval) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.occluded(ray, interval))
+    }
+
+    fn transmittance(&self, ray: &Ray, interval: &Interval) -> f32 {
+        let mut transmittance = 1.0;
+        for item in &self.items {
+            transmittance *= item.transmittance(ray, interval);
+            if transmittance == 0.0 {
+                break;
+            }
+        }
+        transmittance
+    }
+
+    fn pdf_value(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        let weight = 1.0 / self.items.len() as f32;
+        self.items
+            .iter()
+            .map(|item| weight * item.pdf_value(origin, direction))
+            .sum()
+    }
+
+    /// Picks one item at random, so that items which cannot be sampled only leave out their
+    /// share of the samples, matching their zero density in `pdf_value`.
+    fn random(&self, origin: &Vec3) -> Option<Vec3> {
+        let mut rng = ThreadRng::default();
+        self.items[rng.gen_range(0..self.items.len())].random(origin)
+    }
 }
@@ -0,0 +1,137 @@
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::material::material::Material;
+use crate::ray::ray::Ray;
+
+/// Unbounded participating medium filling the whole scene, either homogeneous or thinning out
+/// exponentially with height. It has no boundary to intersect, so the integrator applies it to
+/// every path segment instead.
+pub struct Fog {
+    /// Extinction coefficient per unit length at `base_height`.
+    density: f32,
+    /// Rate at which the density decays per unit of height. Zero for a homogeneous medium.
+    falloff: f32,
+    base_height: f32,
+    phase: Box<dyn Material + Send + Sync>,
+}
+
+impl Fog {
+    pub fn homogeneous<T: Material + Send + Sync + 'static>(density: f32, phase: T) -> Self {
+        Fog::exponential(density, 0.0, 0.0, phase)
+    }
+
+    pub fn exponential<T: Material + Send + Sync + 'static>(
+        density: f32,
+        falloff: f32,
+        base_height: f32,
+        phase: T,
+    ) -> Self {
+        Fog {
+            density,
+            falloff,
+            base_height,
+            phase: Box::new(phase),
+        }
+    }
+
+    /// Fraction of light that makes it along `ray` from its origin to `t_max`.
+    pub fn transmittance(&self, ray: &Ray, t_max: f32) -> f32 {
+        (-self.optical_depth(ray, t_max)).exp()
+    }
+
+    /// Samples a scattering event along `ray` in proportion to the transmittance up to it.
+    /// Returns `None` when the ray gets to `t_max` first.
+    pub fn sample(&self, ray: &Ray, t_max: f32) -> Option<HitRecord<'_>> {
+        let mut rng = ThreadRng::default();
+        let (scale, rate) = self.extinction_along(ray);
+        if scale <= 0.0 {
+            return None;
+        }
+
+        // Invert the optical depth for an exponentially distributed target depth.
+        let depth = -(1.0 - rng.gen::<f32>()).ln();
+        let t = if rate.abs() < 1e-6 {
+            depth / scale
+        } else {
+            let remaining = 1.0 - depth * rate / scale;
+            if remaining <= 0.0 {
+                // The ray climbs out of the fog before reaching that depth.
+                return None;
+            }
+            -remaining.ln() / rate
+        };
+
+        if t >= t_max {
+            return None;
+        }
+
+        Some(HitRecord::volume(t, ray.at(t), self.phase.as_ref()))
+    }
+
+    /// Integral of the extinction along `ray` from its origin to `t`.
+    fn optical_depth(&self, ray: &Ray, t: f32) -> f32 {
+        let (scale, rate) = self.extinction_along(ray);
+        if scale <= 0.0 {
+            0.0
+        } else if rate.abs() < 1e-6 {
+            scale * t
+        } else {
+            -scale * (-rate * t).exp_m1() / rate
+        }
+    }
+
+    /// Extinction along `ray` is `scale * exp(-rate * t)`.
+    fn extinction_along(&self, ray: &Ray) -> (f32, f32) {
+        let height = ray.origin.y - self.base_height;
+        let scale = self.density * (-self.falloff * height).exp() * glm::length(&ray.direction);
+        (scale, self.falloff * ray.direction.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::material::isotropic::Isotropic;
+
+    use super::*;
+
+    #[test]
+    fn homogeneous_transmittance_follows_beer_lambert() {
+        let fog = Fog::homogeneous(0.5, Isotropic::new(Vec3::repeat(1.0)));
+        let ray = Ray::new(Vec3::new(0.0, 3.0, 0.0), Vec3::new(0.0, 0.0, 2.0));
+        let expected = (-0.5_f32 * 2.0 * 1.5).exp();
+        let given = fog.transmittance(&ray, 1.5);
+
+        assert!(
+            (given - expected).abs() < 1e-5,
+            "expected {}, given {}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn sampled_distances_match_exponential_transmittance() {
+        let fog = Fog::exponential(0.8, 0.7, 1.0, Isotropic::new(Vec3::repeat(1.0)));
+        let samples = 20_000;
+
+        for direction in [Vec3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -0.5, 1.0)] {
+            let ray = Ray::new(Vec3::new(0.0, 2.0, 0.0), direction);
+            let expected = fog.transmittance(&ray, 2.0);
+            let passed = (0..samples)
+                .filter(|_| fog.sample(&ray, 2.0).is_none())
+                .count() as f32
+                / samples as f32;
+
+            assert!(
+                (passed - expected).abs() < 0.02,
+                "expected {}, given {}",
+                expected,
+                passed
+            );
+        }
+    }
+}
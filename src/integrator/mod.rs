@@ -0,0 +1,2 @@
+pub mod fog;
+pub mod path_tracer;
@@ -0,0 +1,539 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::geometry::Geometry;
+use crate::geometry::hit_record::HitRecord;
+use crate::geometry::world::World;
+use crate::material::material::Interior;
+use crate::material::scatter_record::Lobe;
+use crate::math::interval::Interval;
+use crate::ray::ray::Ray;
+
+use super::fog::Fog;
+
+/// Maximum number of bounces of each kind along a single path.
+#[derive(Clone, Copy, Debug)]
+pub struct LobeDepths {
+    pub diffuse: u32,
+    pub specular: u32,
+    pub transmission: u32,
+}
+
+impl LobeDepths {
+    pub fn new(diffuse: u32, specular: u32, transmission: u32) -> Self {
+        LobeDepths {
+            diffuse,
+            specular,
+            transmission,
+        }
+    }
+
+    fn limit(&self, lobe: Lobe) -> u32 {
+        match lobe {
+            Lobe::Diffuse => self.diffuse,
+            Lobe::Specular => self.specular,
+            Lobe::Transmission => self.transmission,
+        }
+    }
+}
+
+/// Bounces of each kind a path has taken so far.
+#[derive(Default)]
+struct LobeBounces {
+    diffuse: u32,
+    specular: u32,
+    transmission: u32,
+}
+
+impl LobeBounces {
+    /// Counts one more bounce of `lobe` and returns the new total for that lobe.
+    fn increment(&mut self, lobe: Lobe) -> u32 {
+        let count = match lobe {
+            Lobe::Diffuse => &mut self.diffuse,
+            Lobe::Specular => &mut self.specular,
+            Lobe::Transmission => &mut self.transmission,
+        };
+        *count += 1;
+        *count
+    }
+}
+
+/// Interiors a path is nested in, innermost last, within the atmosphere. Paths enter the
+/// interior of a surface when they transmit through its front face and leave it through its
+/// back face.
+struct MediumStack<'a> {
+    atmosphere: Interior<'a>,
+    nested: Vec<Interior<'a>>,
+}
+
+impl<'a> MediumStack<'a> {
+    /// Paths start out in `camera_medium` if there is one.
+    fn new(atmosphere: Option<&'a Fog>, camera_medium: Option<&'a Fog>) -> Self {
+        let medium = |fog| Interior {
+            fog,
+            absorption: Vec3::zeros(),
+        };
+        MediumStack {
+            atmosphere: medium(atmosphere),
+            nested: camera_medium
+                .map(|fog| medium(Some(fog)))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn current(&self) -> Interior<'a> {
+        self.nested.last().copied().unwrap_or(self.atmosphere)
+    }
+
+    /// Follows a path that transmits through the surface of `hit_record`.
+    fn cross(&mut self, hit_record: &HitRecord<'a>) {
+        if hit_record.front_face {
+            self.nested.push(hit_record.material.interior());
+        } else {
+            self.nested.pop();
+        }
+    }
+}
+
+/// Unidirectional path tracer with next-event estimation.
+/// Every non-delta vertex samples a direction towards `lights` as well as one from its BSDF,
+/// and both estimates are combined with the power heuristic.
+/// Paths are terminated by Russian roulette once they are `min_depth` bounces deep.
+pub struct PathTracer {
+    lights: World,
+    max_depth: u32,
+    lobe_depths: LobeDepths,
+    min_depth: u32,
+    atmosphere: Option<Fog>,
+    camera_medium: Option<Arc<Fog>>,
+}
+
+impl PathTracer {
+    pub fn new(lights: World, max_depth: u32) -> Self {
+        PathTracer {
+            lights,
+            max_depth,
+            lobe_depths: LobeDepths::new(max_depth, max_depth, max_depth),
+            min_depth: 3,
+            atmosphere: None,
+            camera_medium: None,
+        }
+    }
+
+    pub fn set_lobe_depths(&mut self, lobe_depths: LobeDepths) {
+        self.lobe_depths = lobe_depths;
+    }
+
+    pub fn set_russian_roulette_depth(&mut self, min_depth: u32) {
+        self.min_depth = min_depth;
+    }
+
+    /// Fog that fills the whole scene outside of the interiors of surfaces, including the space
+    /// between surfaces and lights.
+    pub fn set_atmosphere(&mut self, atmosphere: Fog) {
+        self.atmosphere = Some(atmosphere);
+    }
+
+    /// Medium surrounding the camera, such as water for an underwater shot. It should be the
+    /// interior of the surface enclosing the camera, which paths leave it through.
+    pub fn set_camera_medium(&mut self, camera_medium: Arc<Fog>) {
+        self.camera_medium = Some(camera_medium);
+    }
+
+    pub fn ray_color<T: Geometry>(&self, ray: &Ray, world: &T) -> Vec3 {
+        let mut rng = ThreadRng::default();
+        let mut radiance = Vec3::zeros();
+        let mut throughput = Vec3::repeat(1.0);
+        let mut ray = *ray;
+        // Density with which the previous vertex sampled `ray`, or `None` when emission along
+        // `ray` could not have been found by light sampling.
+        let mut bsdf_pdf: Option<f32> = None;
+        let mut lobe_bounces = LobeBounces::default();
+        let mut media = MediumStack::new(self.atmosphere.as_ref(), self.camera_medium.as_deref());
+
+        for depth in 0..self.max_depth {
+            let medium = media.current();
+            let surface = world.hit(&ray, &Interval::new(0.001, f32::MAX));
+            let t_surface = surface.as_ref().map_or(f32::INFINITY, |surface| surface.t);
+
+            let (hit_record, on_surface) =
+                match medium.fog.and_then(|fog| fog.sample(&ray, t_surface)) {
+                    Some(scattering) => (scattering, false),
+                    None => match surface {
+                        Some(surface) => (surface, true),
+                        None => {
+                            radiance += throughput.component_mul(&PathTracer::background(&ray));
+                            break;
+                        }
+                    },
+                };
+            let distance = hit_record.t * glm::length(&ray.direction);
+            throughput.component_mul_assign(&medium.absorbed(distance));
+
+            let mut emitted = hit_record.material.emitted(&ray, &hit_record);
+            if let Some(bsdf_pdf) = bsdf_pdf {
+                let light_pdf = self.light_pdf(&ray.origin, &ray.direction);
+                emitted *= power_heuristic(bsdf_pdf, light_pdf);
+            }
+            radiance += throughput.component_mul(&emitted);
+
+            let scatter_record = match hit_record.material.sample(&ray, &hit_record) {
+                Some(scatter_record) if scatter_record.delta || scatter_record.pdf > 0.0 => {
+                    scatter_record
+                }
+                _ => break,
+            };
+
+            let bounces = lobe_bounces.increment(scatter_record.lobe);
+            if bounces > self.lobe_depths.limit(scatter_record.lobe) {
+                break;
+            }
+
+            if !scatter_record.delta {
+                let direct = self.sample_lights(&ray, &hit_record, world, medium);
+                radiance += throughput.component_mul(&direct);
+            }
+
+            throughput.component_mul_assign(&scatter_record.attenuation);
+
+            if depth + 1 >= self.min_depth {
+                let survival = throughput.max().min(1.0);
+                if rng.gen::<f32>() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+
+            bsdf_pdf = if scatter_record.delta {
+                None
+            } else {
+                Some(scatter_record.pdf)
+            };
+            if on_surface && scatter_record.lobe == Lobe::Transmission {
+                media.cross(&hit_record);
+            }
+            // Materials know nothing of motion, so the whole path stays at the camera's time.
+            // The ray cone carries on from its width at the hit, ignoring surface curvature.
+            let (time, spread, width) = (ray.time, ray.spread, ray.width_at(hit_record.t));
+            ray = scatter_record.ray;
+            ray.time = time;
+            ray.spread = spread;
+            ray.width = width;
+        }
+
+        radiance
+    }
+
+    fn sample_lights<T: Geometry>(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        world: &T,
+        medium: Interior,
+    ) -> Vec3 {
+        if self.lights.is_empty() {
+            return Vec3::zeros();
+        }
+
+        let Some(direction) = self.lights.random(&hit_record.p) else {
+            return Vec3::zeros();
+        };
+        let direction = direction.normalize();
+        let light_pdf = self.light_pdf(&hit_record.p, &direction);
+        if light_pdf <= 0.0 {
+            return Vec3::zeros();
+        }
+
+        let material = hit_record.material;
+        let f = material.eval(ray, hit_record, &direction);
+        if f == Vec3::zeros() {
+            return Vec3::zeros();
+        }
+
+        let mut shadow_ray = Ray::new(hit_record.p, direction);
+        shadow_ray.time = ray.time;
+        let light_record = match self
+            .lights
+            .hit(&shadow_ray, &Interval::new(0.001, f32::MAX))
+        {
+            Some(light_record) => light_record,
+            None => return Vec3::zeros(),
+        };
+
+        let unoccluded = Interval::new(0.001, light_record.t - 0.001);
+        let mut transmittance = world.transmittance(&shadow_ray, &unoccluded);
+        if let Some(fog) = medium.fog {
+            transmittance *= fog.transmittance(&shadow_ray, light_record.t);
+        }
+        if transmittance == 0.0 {
+            return Vec3::zeros();
+        }
+        let absorbed = medium.absorbed(light_record.t);
+
+        let emitted = light_record.material.emitted(&shadow_ray, &light_record);
+
+        let bsdf_pdf = material.pdf(ray, hit_record, &direction);
+        let weight = power_heuristic(light_pdf, bsdf_pdf);
+
+        f.component_mul(&emitted).component_mul(&absorbed) * transmittance * weight / light_pdf
+    }
+
+    fn light_pdf(&self, origin: &Vec3, direction: &Vec3) -> f32 {
+        if self.lights.is_empty() {
+            0.0
+        } else {
+            self.lights.pdf_value(origin, direction)
+        }
+    }
+
+    fn background(ray: &Ray) -> Vec3 {
+        let unit_direction = ray.direction;
+        let a = 0.5 * (unit_direction.y + 1.0);
+        glm::lerp(&Vec3::repeat(1.0), &Vec3::new(0.5, 0.7, 1.0), a)
+    }
+}
+
+/// Veach's power heuristic with an exponent of two, for one sample from each strategy.
+fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    let a = pdf.powi(2);
+    let b = other_pdf.powi(2);
+    if a + b > 0.0 {
+        a / (a + b)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::cuboid::Cuboid;
+    use crate::geometry::quad::Quad;
+    use crate::geometry::sphere::Sphere;
+    use crate::material::dielectric::Dielectric;
+    use crate::material::diffuse_light::DiffuseLight;
+    use crate::material::isotropic::Isotropic;
+    use crate::material::lambertian::Lambertian;
+    use crate::material::material::Material;
+    use crate::material::metal::Metal;
+
+    use super::*;
+
+    fn absorbing_fog() -> Fog {
+        Fog::homogeneous(1.0, Isotropic::new(Vec3::zeros()))
+    }
+
+    /// Diffuse floor at y = 0 under the sky, and a ray coming down onto it.
+    fn floor(albedo: f32) -> (World, Ray) {
+        let floor = Quad::new(
+            Vec3::new(-100.0, 0.0, 100.0),
+            Vec3::new(200.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -200.0),
+            Lambertian::new(Vec3::repeat(albedo)),
+        );
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        (World::new(vec![Box::new(floor)]), ray)
+    }
+
+    fn same(medium: Option<&Fog>, expected: Option<&Fog>) -> bool {
+        match (medium, expected) {
+            (Some(medium), Some(expected)) => std::ptr::eq(medium, expected),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn medium_stack_follows_interfaces() {
+        let atmosphere = absorbing_fog();
+        let water = Arc::new(absorbing_fog());
+        let mut surface = Dielectric::new(1.33);
+        surface.set_interior(water.clone());
+        let glass = Dielectric::new(1.5);
+        let hit = |material, front_face| {
+            HitRecord::new(
+                1.0,
+                Vec3::zeros(),
+                Vec3::y(),
+                front_face,
+                0.0,
+                0.0,
+                material,
+            )
+        };
+
+        let mut media = MediumStack::new(Some(&atmosphere), Some(&water));
+        assert!(same(media.current().fog, Some(&water)));
+
+        // Through a glass ball under water, then out of the water and back in.
+        let steps: [(&dyn Material, bool, Option<&Fog>); 4] = [
+            (&glass, true, None),
+            (&glass, false, Some(&water)),
+            (&surface, false, Some(&atmosphere)),
+            (&surface, true, Some(&water)),
+        ];
+        for (material, front_face, expected) in steps {
+            media.cross(&hit(material, front_face));
+            assert!(same(media.current().fog, expected));
+        }
+
+        // Glass in the atmosphere keeps it out until the path leaves again.
+        let mut media = MediumStack::new(Some(&atmosphere), None);
+        media.cross(&hit(&glass, true));
+        assert!(same(media.current().fog, None));
+        media.cross(&hit(&glass, false));
+        assert!(same(media.current().fog, Some(&atmosphere)));
+    }
+
+    #[test]
+    fn nested_surfaces_absorb_through_outer_interior() {
+        // Index-matched, so that a ray through the centers passes straight through both.
+        let mut liquid = Dielectric::new(1.0);
+        liquid.set_transmittance(Vec3::new(0.5, 0.25, 1.0), 1.0);
+        let world = World::new(vec![
+            Box::new(Sphere::new(Vec3::zeros(), 2.0, liquid)),
+            Box::new(Sphere::new(Vec3::zeros(), 1.0, Dielectric::new(1.0))),
+        ]);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::z());
+        let mut integrator = PathTracer::new(World::new(vec![]), 8);
+        integrator.set_russian_roulette_depth(8);
+
+        // One unit of liquid on either side of the clear ball.
+        let given = integrator.ray_color(&ray, &world);
+        let expected = PathTracer::background(&ray).component_mul(&Vec3::new(0.25, 0.0625, 1.0));
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn atmosphere_absorbs_background() {
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+        let mut integrator = PathTracer::new(World::new(vec![]), 4);
+        assert_ne!(
+            integrator.ray_color(&ray, &World::new(vec![])),
+            Vec3::zeros()
+        );
+
+        integrator.set_atmosphere(absorbing_fog());
+        assert_eq!(
+            integrator.ray_color(&ray, &World::new(vec![])),
+            Vec3::zeros()
+        );
+    }
+
+    #[test]
+    fn camera_medium_absorbs_background() {
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, -1.0, 0.0));
+        let mut integrator = PathTracer::new(World::new(vec![]), 4);
+        integrator.set_camera_medium(Arc::new(absorbing_fog()));
+        assert_eq!(
+            integrator.ray_color(&ray, &World::new(vec![])),
+            Vec3::zeros()
+        );
+    }
+
+    #[test]
+    fn lobe_depths_limit_each_kind() {
+        let (world, ray) = floor(1.0);
+        let mut integrator = PathTracer::new(World::new(vec![]), 8);
+
+        // The only way to the sky is one diffuse bounce off the floor.
+        integrator.set_lobe_depths(LobeDepths::new(0, 8, 8));
+        assert_eq!(integrator.ray_color(&ray, &world), Vec3::zeros());
+
+        integrator.set_lobe_depths(LobeDepths::new(1, 0, 0));
+        assert_ne!(integrator.ray_color(&ray, &world), Vec3::zeros());
+    }
+
+    #[test]
+    fn russian_roulette_terminates_without_bias() {
+        let (world, ray) = floor(0.5);
+        let mut integrator = PathTracer::new(World::new(vec![]), 8);
+        let samples = 20_000;
+        let mean = |integrator: &PathTracer| {
+            let colors: Vec<f32> = (0..samples)
+                .map(|_| integrator.ray_color(&ray, &world).x)
+                .collect();
+            let terminated = colors.iter().filter(|&&x| x == 0.0).count();
+            (colors.iter().sum::<f32>() / samples as f32, terminated)
+        };
+
+        integrator.set_russian_roulette_depth(8);
+        let (expected, terminated) = mean(&integrator);
+        assert_eq!(terminated, 0);
+
+        // Half of the paths survive the floor, carrying twice the light.
+        integrator.set_russian_roulette_depth(0);
+        let (given, terminated) = mean(&integrator);
+        assert!(terminated > samples / 3, "given {}", terminated);
+        assert!(
+            (given - expected).abs() < 0.02,
+            "expected {}, given {}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn paths_between_mirrors_stop_at_max_depth() {
+        let mirror = |y: f32, u: Vec3| {
+            Box::new(Quad::new(
+                Vec3::new(-1.0, y, -1.0),
+                u,
+                Vec3::new(0.0, 0.0, 2.0),
+                Metal::new(Vec3::repeat(1.0), 0.0),
+            )) as Box<dyn Geometry>
+        };
+        // Facing each other, so that a vertical ray bounces between them forever.
+        let world = World::new(vec![
+            mirror(0.0, Vec3::new(2.0, 0.0, 0.0)),
+            mirror(1.0, Vec3::new(2.0, 0.0, 0.0)),
+        ]);
+        let ray = Ray::new(Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let max_depth = 100_000;
+        let mut integrator = PathTracer::new(World::new(vec![]), max_depth);
+        integrator.set_russian_roulette_depth(max_depth);
+
+        assert_eq!(integrator.ray_color(&ray, &world), Vec3::zeros());
+    }
+
+    #[test]
+    fn lights_without_sampling_are_found_by_bsdf_sampling() {
+        let lamp = Arc::new(Cuboid::new(
+            Vec3::new(-0.5, 2.0, -0.5),
+            Vec3::new(0.5, 3.0, 0.5),
+            DiffuseLight::new(Vec3::repeat(4.0)),
+        ));
+        let (mut world, ray) = floor(0.5);
+        world.add(Box::new(lamp.clone()));
+        let integrator = PathTracer::new(World::new(vec![Box::new(lamp)]), 8);
+
+        for _ in 0..100 {
+            let color = integrator.ray_color(&ray, &world);
+            assert!(color.iter().all(|x| x.is_finite()), "given {:?}", color);
+        }
+
+        let up = Ray::new(Vec3::zeros(), Vec3::y());
+        assert_eq!(integrator.ray_color(&up, &world), Vec3::repeat(4.0));
+    }
+
+    #[test]
+    fn power_heuristic_weights_sum_to_one() {
+        for (a, b) in [(0.5, 2.0), (1.0, 1.0), (3.0, 0.0)] {
+            let given = power_heuristic(a, b) + power_heuristic(b, a);
+            assert!((given - 1.0).abs() < 1e-6, "given {}", given);
+        }
+    }
+
+    #[test]
+    fn power_heuristic_without_light_pdf() {
+        assert_eq!(power_heuristic(0.7, 0.0), 1.0);
+    }
+}
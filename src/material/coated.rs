@@ -0,0 +1,202 @@
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::ray::ray::Ray;
+
+use super::fresnel;
+use super::material::Material;
+use super::microfacet::{self, Ggx};
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Floor on the chance of sampling the coat, so its highlights converge at normal incidence.
+const MIN_COAT_PROBABILITY: f32 = 0.25;
+
+/// Dielectric coat, like varnish or car paint lacquer, layered over a `base` material.
+///
+/// Light reflects off the coat with the Fresnel reflectance of `ior`; whatever gets through on
+/// the way in and out reaches the base, so the layer never reflects more than it receives.
+/// Directions are not bent by the coat when evaluating the base.
+pub struct Coated<T: Material> {
+    base: T,
+    ior: f32,
+    roughness: f32,
+    /// Transmittance of the coat for a round trip at normal incidence.
+    tint: Vec3,
+}
+
+impl<T: Material> Coated<T> {
+    pub fn new(base: T, ior: f32) -> Self {
+        Coated {
+            base,
+            ior,
+            roughness: 0.0,
+            tint: Vec3::repeat(1.0),
+        }
+    }
+
+    /// Perceptual roughness of the coat in (0..1). Zero is a polished finish.
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness;
+    }
+
+    /// Color the coat absorbs towards, such as amber for varnish. White is a clear coat.
+    pub fn set_tint(&mut self, tint: Vec3) {
+        self.tint = tint;
+    }
+
+    /// Cosine of the direction refracted into the coat.
+    fn refracted_cos(&self, cos_theta: f32) -> f32 {
+        (1.0 - (1.0 - cos_theta.powi(2)) / self.ior.powi(2)).sqrt()
+    }
+
+    /// Share of the light that crosses the coat into the base along `cos_o` and back out
+    /// along `cos_i`, including absorption over the slanted path through the layer.
+    fn transmittance(&self, cos_o: f32, cos_i: f32) -> Vec3 {
+        let (cos_o, cos_i) = (cos_o.abs().min(1.0), cos_i.abs().min(1.0));
+        let fresnel = (1.0 - fresnel::dielectric(cos_o, self.ior))
+            * (1.0 - fresnel::dielectric(cos_i, self.ior));
+        let path = 0.5 * (1.0 / self.refracted_cos(cos_o) + 1.0 / self.refracted_cos(cos_i));
+
+        self.tint.map(|channel| channel.powf(path)) * fresnel
+    }
+
+    fn coat_probability(&self, cos_o: f32) -> f32 {
+        fresnel::dielectric(cos_o, self.ior).max(MIN_COAT_PROBABILITY)
+    }
+}
+
+impl<T: Material> Material for Coated<T> {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+        let coat_probability = self.coat_probability(wo.z);
+        let mut rng = ThreadRng::default();
+
+        let (direction, lobe) = if rng.gen::<f32>() < coat_probability {
+            if ggx.is_smooth() {
+                let direction = onb.local_to_world(&Vec3::new(-wo.x, -wo.y, wo.z));
+                let attenuation =
+                    Vec3::repeat(fresnel::dielectric(wo.z, self.ior) / coat_probability);
+                let scattered = Ray::new(hit_record.p, direction);
+                return Some(ScatterRecord::delta(scattered, attenuation, Lobe::Specular));
+            }
+
+            let wm = ggx.sample_visible_normal(&wo, rng.gen(), rng.gen());
+            let wi = microfacet::reflect(&wo, &wm);
+            (onb.local_to_world(&wi), Lobe::Specular)
+        } else {
+            let scatter_record = self.base.sample(ray, hit_record)?;
+
+            if scatter_record.delta {
+                let cos_i = glm::dot(
+                    &scatter_record.ray.direction.normalize(),
+                    &hit_record.normal,
+                );
+                let attenuation = scatter_record
+                    .attenuation
+                    .component_mul(&(self.transmittance(wo.z, cos_i) / (1.0 - coat_probability)));
+                return Some(ScatterRecord::delta(
+                    scatter_record.ray,
+                    attenuation,
+                    scatter_record.lobe,
+                ));
+            }
+            (scatter_record.ray.direction, scatter_record.lobe)
+        };
+
+        let pdf = self.pdf(ray, hit_record, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let attenuation = self.eval(ray, hit_record, &direction) / pdf;
+        let scattered = Ray::new(hit_record.p, direction);
+        Some(ScatterRecord::new(scattered, attenuation, pdf, lobe))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+
+        let base = self
+            .base
+            .eval(ray, hit_record, direction)
+            .component_mul(&self.transmittance(wo.z, wi.z));
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z <= 0.0 {
+            return base;
+        }
+
+        let wm = (wo + wi).normalize();
+        let fresnel = fresnel::dielectric(glm::dot(&wo, &wm), self.ior);
+        let coat = fresnel * ggx.d(&wm) * ggx.g(&wo, &wi) / (4.0 * wo.z);
+        base + Vec3::repeat(coat)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+        let coat_probability = self.coat_probability(wo.z);
+
+        let base = (1.0 - coat_probability) * self.base.pdf(ray, hit_record, direction);
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z <= 0.0 {
+            return base;
+        }
+
+        let wm = (wo + wi).normalize();
+        base + coat_probability * ggx.visible_d(&wo, &wm) / (4.0 * glm::dot(&wo, &wm))
+    }
+
+    fn emitted(&self, ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        let cos_o = glm::dot(&-ray.direction.normalize(), &hit_record.normal);
+        self.base.emitted(ray, hit_record) * (1.0 - fresnel::dielectric(cos_o, self.ior))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::lambertian::Lambertian;
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let mut material = Coated::new(Lambertian::new(Vec3::new(0.6, 0.3, 0.1)), 1.5);
+        material.set_roughness(0.3);
+        material.set_tint(Vec3::new(0.9, 0.7, 0.4));
+        let ray = Ray::new(
+            Vec3::new(0.7, 1.0, 0.2),
+            Vec3::new(-0.7, -1.0, -0.2).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+    }
+
+    #[test]
+    fn does_not_create_energy() {
+        let material = Coated::new(Lambertian::new(Vec3::repeat(1.0)), 1.5);
+        let ray = Ray::new(
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(-1.0, -1.0, 0.0).normalize(),
+        );
+        let hit_record = hit_record(&material, true);
+        let samples = 20_000;
+
+        let albedo = (0..samples)
+            .filter_map(|_| material.sample(&ray, &hit_record))
+            .map(|scatter_record| scatter_record.attenuation.x)
+            .sum::<f32>()
+            / samples as f32;
+
+        assert!(albedo <= 1.0 && albedo > 0.8, "given {}", albedo);
+    }
+}
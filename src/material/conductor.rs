@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::fresnel;
+use super::material::Material;
+use super::microfacet::{self, Ggx};
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Rough metal described by a GGX microfacet distribution and a complex index of refraction.
+pub struct Conductor {
+    eta: Vec3,
+    k: Vec3,
+    /// Only the first channel of the texture is used.
+    roughness: Arc<dyn Texture>,
+    anisotropy: f32,
+}
+
+impl Conductor {
+    pub fn new(eta: Vec3, k: Vec3, roughness: f32) -> Self {
+        Conductor::textured(eta, k, Arc::new(SolidColor::new(Vec3::repeat(roughness))))
+    }
+
+    pub fn textured(eta: Vec3, k: Vec3, roughness: Arc<dyn Texture>) -> Self {
+        Conductor {
+            eta,
+            k,
+            roughness,
+            anisotropy: 0.0,
+        }
+    }
+
+    /// Conductor whose reflectance at normal incidence is `albedo`.
+    pub fn from_albedo(albedo: Vec3, roughness: f32) -> Self {
+        // With k = 0 the normal incidence reflectance is ((eta - 1) / (eta + 1))^2.
+        let eta = albedo.map(|f0| {
+            let r = f0.clamp(0.0, 0.99).sqrt();
+            (1.0 + r) / (1.0 - r)
+        });
+        Conductor::new(eta, Vec3::zeros(), roughness)
+    }
+
+    pub fn gold(roughness: f32) -> Self {
+        Conductor::new(
+            Vec3::new(0.143, 0.374, 1.442),
+            Vec3::new(3.983, 2.386, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f32) -> Self {
+        Conductor::new(
+            Vec3::new(0.200, 0.924, 1.102),
+            Vec3::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f32) -> Self {
+        Conductor::new(
+            Vec3::new(1.657, 0.880, 0.521),
+            Vec3::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    /// Stretches highlights along the surface tangent, the direction of growing `u`; 0 is
+    /// isotropic, 1 is fully stretched.
+    pub fn set_anisotropy(&mut self, anisotropy: f32) {
+        self.anisotropy = anisotropy;
+    }
+
+    fn distribution(&self, hit_record: &HitRecord) -> Ggx {
+        let roughness = self.roughness.value(hit_record).x.clamp(0.0, 1.0);
+        Ggx::from_roughness(roughness, self.anisotropy)
+    }
+}
+
+impl Material for Conductor {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let onb = Onb::with_tangent(&hit_record.normal, &hit_record.tangent);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let ggx = self.distribution(hit_record);
+
+        if ggx.is_smooth() {
+            let direction = onb.local_to_world(&Vec3::new(-wo.x, -wo.y, wo.z));
+            let attenuation = fresnel::conductor(wo.z, &self.eta, &self.k);
+            let scattered = Ray::new(hit_record.p, direction);
+            return Some(ScatterRecord::delta(scattered, attenuation, Lobe::Specular));
+        }
+
+        let mut rng = ThreadRng::default();
+        let wm = ggx.sample_visible_normal(&wo, rng.gen(), rng.gen());
+        let wi = microfacet::reflect(&wo, &wm);
+
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return None;
+        }
+
+        // f * cos / pdf simplifies to F * G / G1 for visible normal sampling.
+        let fresnel = fresnel::conductor(glm::dot(&wo, &wm), &self.eta, &self.k);
+        let attenuation = fresnel * ggx.g(&wo, &wi) / ggx.g1(&wo);
+        let pdf = ggx.visible_d(&wo, &wm) / (4.0 * glm::dot(&wo, &wm));
+        let scattered = Ray::new(hit_record.p, onb.local_to_world(&wi));
+
+        Some(ScatterRecord::new(
+            scattered,
+            attenuation,
+            pdf,
+            Lobe::Specular,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let onb = Onb::with_tangent(&hit_record.normal, &hit_record.tangent);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let ggx = self.distribution(hit_record);
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z <= 0.0 {
+            return Vec3::zeros();
+        }
+
+        let wm = (wo + wi).normalize();
+        let fresnel = fresnel::conductor(glm::dot(&wo, &wm), &self.eta, &self.k);
+        fresnel * ggx.d(&wm) * ggx.g(&wo, &wi) / (4.0 * wo.z)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let onb = Onb::with_tangent(&hit_record.normal, &hit_record.tangent);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let ggx = self.distribution(hit_record);
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z <= 0.0 {
+            return 0.0;
+        }
+
+        let wm = (wo + wi).normalize();
+        ggx.visible_d(&wo, &wm) / (4.0 * glm::dot(&wo, &wm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::assert_consistent;
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let mut material = Conductor::gold(0.5);
+        material.set_anisotropy(0.6);
+        let ray = Ray::new(
+            Vec3::new(1.0, 1.0, 0.3),
+            Vec3::new(-1.0, -1.0, -0.3).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+    }
+
+    #[test]
+    fn from_albedo_reflects_albedo_at_normal_incidence() {
+        let albedo = Vec3::new(0.9, 0.6, 0.2);
+        let material = Conductor::from_albedo(albedo, 0.0);
+        let given = fresnel::conductor(1.0, &material.eta, &material.k);
+
+        assert!(
+            glm::equal_eps(&albedo, &given, 1e-4).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            albedo,
+            given
+        );
+    }
+}
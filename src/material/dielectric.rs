@@ -1,18 +1,49 @@
+use std::sync::Arc;
+
 use glm::Vec3;
 use rand::random;
 
 use crate::geometry::hit_record::HitRecord;
+use crate::integrator::fog::Fog;
+use crate::math::onb::Onb;
 use crate::ray::ray::Ray;
 
-use super::material::Material;
+use super::fresnel;
+use super::material::{Interior, Material};
+use super::microfacet::{self, Ggx};
+use super::scatter_record::{Lobe, ScatterRecord};
 
 pub struct Dielectric {
     refraction_index: f32,
+    roughness: f32,
+    /// Beer-Lambert absorption coefficient of the interior, per unit length.
+    absorption: Vec3,
+    fog: Option<Arc<Fog>>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f32) -> Self {
-        Dielectric { refraction_index }
+        Dielectric {
+            refraction_index,
+            roughness: 0.0,
+            absorption: Vec3::zeros(),
+            fog: None,
+        }
+    }
+
+    /// Perceptual roughness of the surface in (0..1). Zero is polished glass, higher is frosted.
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness;
+    }
+
+    /// Tints the interior so that white light keeps `transmittance` after traveling `distance`.
+    pub fn set_transmittance(&mut self, transmittance: Vec3, distance: f32) {
+        self.absorption = transmittance.map(|channel| -channel.max(1e-6).ln() / distance);
+    }
+
+    /// Fills the interior with a scattering medium, such as the water below a water surface.
+    pub fn set_interior(&mut self, interior: Arc<Fog>) {
+        self.fog = Some(interior);
     }
 
     fn reflectance(cosine: f32, refraction_index: f32) -> f32 {
@@ -20,28 +51,210 @@ impl Dielectric {
         let r02 = r0.powi(2);
         r02 + (1.0 - r02) * (1.0 - cosine).powi(5)
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Vec3)> {
-        let attenuation = Vec3::new(1.0, 1.0, 1.0);
-        let ri = if hit_record.front_face {
-            1.0 / self.refraction_index
-        } else {
+    /// Index of refraction of the far side over that of the side the ray arrives from.
+    fn relative_eta(&self, hit_record: &HitRecord) -> f32 {
+        if hit_record.front_face {
             self.refraction_index
-        };
+        } else {
+            1.0 / self.refraction_index
+        }
+    }
+
+    fn sample_smooth(&self, ray: &Ray, hit_record: &HitRecord) -> ScatterRecord {
+        let ri = 1.0 / self.relative_eta(hit_record);
         let unit_direction = ray.direction.normalize();
         let cos_theta = glm::dot(&-unit_direction, &hit_record.normal).min(1.0);
         let sin_theta = (1.0 - cos_theta.powi(2)).sqrt();
 
-        let direction = if ri * sin_theta > 1.0 || Dielectric::reflectance(cos_theta, ri) > random()
-        {
-            glm::reflect_vec(&unit_direction, &hit_record.normal)
+        let (direction, lobe) =
+            if ri * sin_theta > 1.0 || Dielectric::reflectance(cos_theta, ri) > random() {
+                (
+                    glm::reflect_vec(&unit_direction, &hit_record.normal),
+                    Lobe::Specular,
+                )
+            } else {
+                (
+                    glm::refract_vec(&unit_direction, &hit_record.normal, ri),
+                    Lobe::Transmission,
+                )
+            };
+
+        let scattered = Ray::new(hit_record.p, direction);
+        ScatterRecord::delta(scattered, Vec3::repeat(1.0), lobe)
+    }
+
+    /// Samples the rough interface of Walter et al. 2007 with visible normals.
+    fn sample_rough(&self, ray: &Ray, hit_record: &HitRecord, ggx: &Ggx) -> Option<ScatterRecord> {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let eta = self.relative_eta(hit_record);
+
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let wm = ggx.sample_visible_normal(&wo, random(), random());
+        let cos_o = glm::dot(&wo, &wm);
+        let reflectance = fresnel::dielectric(cos_o, eta);
+
+        let (wi, pdf, lobe) = if random::<f32>() < reflectance {
+            let wi = microfacet::reflect(&wo, &wm);
+            if wi.z <= 0.0 {
+                return None;
+            }
+            let pdf = ggx.visible_d(&wo, &wm) / (4.0 * cos_o) * reflectance;
+            (wi, pdf, Lobe::Specular)
         } else {
-            glm::refract_vec(&unit_direction, &hit_record.normal, ri)
+            let sin2_t = (1.0 - cos_o.powi(2)) / eta.powi(2);
+            let cos_t = (1.0 - sin2_t).max(0.0).sqrt();
+            let wi = -wo / eta + (cos_o / eta - cos_t) * wm;
+            if wi.z >= 0.0 {
+                return None;
+            }
+            let denominator = (glm::dot(&wi, &wm) + cos_o / eta).powi(2);
+            let jacobian = glm::dot(&wi, &wm).abs() / denominator;
+            let pdf = ggx.visible_d(&wo, &wm) * jacobian * (1.0 - reflectance);
+            (wi, pdf, Lobe::Transmission)
         };
 
-        let scattered = Ray::new(hit_record.p, direction);
-        Some((scattered, attenuation))
+        // f * cos / pdf simplifies to G / G1 for both lobes. The 1 / eta^2 radiance scaling is
+        // omitted, as in the smooth case; it cancels for paths that leave the object again.
+        let attenuation = Vec3::repeat(ggx.g(&wo, &wi) / ggx.g1(&wo));
+        let scattered = Ray::new(hit_record.p, onb.local_to_world(&wi));
+
+        Some(ScatterRecord::new(scattered, attenuation, pdf, lobe))
+    }
+
+    /// Generalized half vector of `wo` and `wi`, facing the side of `wo`, or `None` when the
+    /// configuration is impossible.
+    fn half_vector(wo: &Vec3, wi: &Vec3, eta: f32) -> Option<Vec3> {
+        let reflection = wi.z > 0.0;
+        let wm = if reflection { wo + wi } else { wo + eta * wi };
+
+        if glm::length2(&wm) == 0.0 {
+            return None;
+        }
+
+        let wm = if wm.z < 0.0 { -wm } else { wm }.normalize();
+        let facing = glm::dot(wo, &wm) > 0.0 && (glm::dot(wi, &wm) > 0.0) == reflection;
+        facing.then_some(wm)
+    }
+}
+
+impl Material for Dielectric {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+
+        if ggx.is_smooth() {
+            Some(self.sample_smooth(ray, hit_record))
+        } else {
+            self.sample_rough(ray, hit_record, &ggx)
+        }
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let eta = self.relative_eta(hit_record);
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z == 0.0 {
+            return Vec3::zeros();
+        }
+
+        let wm = match Dielectric::half_vector(&wo, &wi, eta) {
+            Some(wm) => wm,
+            None => return Vec3::zeros(),
+        };
+        let cos_o = glm::dot(&wo, &wm);
+        let reflectance = fresnel::dielectric(cos_o, eta);
+
+        let value = if wi.z > 0.0 {
+            reflectance * ggx.d(&wm) * ggx.g(&wo, &wi) / (4.0 * wo.z)
+        } else {
+            let cos_i = glm::dot(&wi, &wm);
+            let denominator = (cos_i + cos_o / eta).powi(2);
+            (1.0 - reflectance) * ggx.d(&wm) * ggx.g(&wo, &wi) * (cos_i * cos_o).abs()
+                / (wo.z * denominator)
+        };
+
+        Vec3::repeat(value)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let ggx = Ggx::from_roughness(self.roughness, 0.0);
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+        let eta = self.relative_eta(hit_record);
+
+        if ggx.is_smooth() || wo.z <= 0.0 || wi.z == 0.0 {
+            return 0.0;
+        }
+
+        let wm = match Dielectric::half_vector(&wo, &wi, eta) {
+            Some(wm) => wm,
+            None => return 0.0,
+        };
+        let cos_o = glm::dot(&wo, &wm);
+        let reflectance = fresnel::dielectric(cos_o, eta);
+
+        if wi.z > 0.0 {
+            ggx.visible_d(&wo, &wm) / (4.0 * cos_o) * reflectance
+        } else {
+            let cos_i = glm::dot(&wi, &wm);
+            let denominator = (cos_i + cos_o / eta).powi(2);
+            ggx.visible_d(&wo, &wm) * cos_i.abs() / denominator * (1.0 - reflectance)
+        }
+    }
+
+    fn interior(&self) -> Interior<'_> {
+        Interior {
+            fog: self.fog.as_deref(),
+            absorption: self.absorption,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn rough_sample_is_consistent_with_eval_and_pdf() {
+        let mut material = Dielectric::new(1.5);
+        material.set_roughness(0.6);
+        let ray = Ray::new(
+            Vec3::new(0.5, 1.0, 0.2),
+            Vec3::new(-0.5, -1.0, -0.2).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-2);
+        assert_consistent(&material, &ray, false, 1e-2);
+    }
+
+    #[test]
+    fn absorbs_through_interior() {
+        let mut material = Dielectric::new(1.5);
+        material.set_transmittance(Vec3::new(0.5, 0.25, 1.0), 2.0);
+
+        let given = material.interior().absorbed(2.0);
+        let expected = Vec3::new(0.5, 0.25, 1.0);
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+
+        // The surface itself is clear from either side.
+        let ray = Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        for front_face in [true, false] {
+            let record = material.sample(&ray, &hit_record(&material, front_face));
+            assert_eq!(record.unwrap().attenuation, Vec3::repeat(1.0));
+        }
     }
 }
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::ScatterRecord;
+
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Vec3) -> Self {
+        DiffuseLight::textured(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn textured(emit: Arc<dyn Texture>) -> Self {
+        DiffuseLight { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(&self, _ray: &Ray, _hit_record: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        if hit_record.front_face {
+            self.emit.value(hit_record)
+        } else {
+            Vec3::zeros()
+        }
+    }
+}
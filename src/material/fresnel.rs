@@ -0,0 +1,97 @@
+use glm::Vec3;
+
+/// Unpolarized reflectance of a conductor with complex index of refraction `eta + i k`,
+/// evaluated per color channel.
+pub fn conductor(cos_theta: f32, eta: &Vec3, k: &Vec3) -> Vec3 {
+    let cos2 = cos_theta.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+
+    Vec3::from_fn(|i, _| {
+        let (eta2, k2) = (eta[i].powi(2), k[i].powi(2));
+        let t0 = eta2 - k2 - sin2;
+        let a2_plus_b2 = (t0.powi(2) + 4.0 * eta2 * k2).sqrt();
+        let t1 = a2_plus_b2 + cos2;
+        let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+        let t2 = 2.0 * cos_theta * a;
+        let rs = (t1 - t2) / (t1 + t2);
+        let t3 = cos2 * a2_plus_b2 + sin2.powi(2);
+        let t4 = t2 * sin2;
+        let rp = rs * (t3 - t4) / (t3 + t4);
+
+        0.5 * (rp + rs)
+    })
+}
+
+/// Unpolarized reflectance at a boundary with relative index of refraction `eta`
+/// (transmitted over incident side). `cos_theta` is negative when arriving from the inside.
+pub fn dielectric(cos_theta: f32, eta: f32) -> f32 {
+    let (cos_i, eta) = if cos_theta < 0.0 {
+        (-cos_theta, 1.0 / eta)
+    } else {
+        (cos_theta, eta)
+    };
+    let cos_i = cos_i.min(1.0);
+    let sin2_t = (1.0 - cos_i.powi(2)) / eta.powi(2);
+
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (parallel.powi(2) + perpendicular.powi(2))
+}
+
+/// Schlick's approximation, blending from the normal incidence reflectance `f0` to white.
+pub fn schlick(cos_theta: f32, f0: &Vec3) -> Vec3 {
+    let weight = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+    glm::lerp(f0, &Vec3::repeat(1.0), weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conductor_at_normal_incidence() {
+        let eta = Vec3::new(0.2, 1.0, 1.5);
+        let k = Vec3::new(3.9, 2.4, 0.0);
+        let given = conductor(1.0, &eta, &k);
+
+        for i in 0..3 {
+            let expected =
+                ((eta[i] - 1.0).powi(2) + k[i].powi(2)) / ((eta[i] + 1.0).powi(2) + k[i].powi(2));
+            assert!(
+                (given[i] - expected).abs() < 1e-5,
+                "expected {}, given {}",
+                expected,
+                given[i]
+            );
+        }
+    }
+
+    #[test]
+    fn conductor_at_grazing_angle() {
+        let given = conductor(0.0, &Vec3::repeat(0.2), &Vec3::repeat(3.9));
+        assert!(
+            glm::equal_eps(&given, &Vec3::repeat(1.0), 1e-5)
+                .iter()
+                .all(|&x| x),
+            "given {:?}",
+            given
+        );
+    }
+
+    #[test]
+    fn dielectric_at_normal_incidence() {
+        let given = dielectric(1.0, 1.5);
+        assert!((given - 0.04).abs() < 1e-6, "given {}", given);
+    }
+
+    #[test]
+    fn dielectric_total_internal_reflection() {
+        assert_eq!(dielectric(-0.3, 1.5), 1.0);
+        assert!(dielectric(-0.9, 1.5) < 1.0);
+    }
+}
@@ -0,0 +1,123 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Henyey-Greenstein phase function. The asymmetry `g` in (-1..1) is the mean cosine of the
+/// scattering angle: positive values scatter forward like haze and clouds, negative backward.
+pub struct HenyeyGreenstein {
+    albedo: Arc<dyn Texture>,
+    g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Vec3, g: f32) -> Self {
+        HenyeyGreenstein::textured(Arc::new(SolidColor::new(albedo)), g)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>, g: f32) -> Self {
+        HenyeyGreenstein {
+            albedo,
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    /// Density of scattering by an angle whose cosine is `cos_theta`.
+    fn phase(&self, cos_theta: f32) -> f32 {
+        let g2 = self.g.powi(2);
+        let denominator = 1.0 + g2 - 2.0 * self.g * cos_theta;
+        (1.0 - g2) / (4.0 * PI * denominator * denominator.max(0.0).sqrt())
+    }
+
+    fn sample_cos_theta(&self, u: f32) -> f32 {
+        if self.g.abs() < 1e-3 {
+            return 1.0 - 2.0 * u;
+        }
+
+        let g2 = self.g.powi(2);
+        let s = (1.0 - g2) / (1.0 - self.g + 2.0 * self.g * u);
+        ((1.0 + g2 - s.powi(2)) / (2.0 * self.g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = ThreadRng::default();
+        let forward = ray.direction.normalize();
+        let cos_theta = self.sample_cos_theta(rng.gen());
+        let sin_theta = (1.0 - cos_theta.powi(2)).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f32>();
+
+        let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        let direction = Onb::new(&forward).local_to_world(&local);
+        let scattered = Ray::new(hit_record.p, direction);
+
+        // Volume scattering is budgeted with diffuse bounces.
+        Some(ScatterRecord::new(
+            scattered,
+            self.albedo.value(hit_record),
+            self.phase(cos_theta),
+            Lobe::Diffuse,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        self.albedo.value(hit_record) * self.pdf(ray, hit_record, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, _hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let cos_theta = glm::dot(&ray.direction.normalize(), &direction.normalize());
+        self.phase(cos_theta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phase_integrates_to_one() {
+        let material = HenyeyGreenstein::new(Vec3::repeat(1.0), 0.7);
+        let samples = 200_000;
+        let mut rng = rand::thread_rng();
+
+        // Uniform sampling of cos theta with density 1 / (4 PI) over the sphere.
+        let estimate = (0..samples)
+            .map(|_| material.phase(1.0 - 2.0 * rng.gen::<f32>()) * 4.0 * PI)
+            .sum::<f32>()
+            / samples as f32;
+
+        assert!((estimate - 1.0).abs() < 0.05, "given {}", estimate);
+    }
+
+    #[test]
+    fn mean_cosine_is_asymmetry() {
+        let material = HenyeyGreenstein::new(Vec3::repeat(1.0), 0.6);
+        let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, 1.0));
+        let hit_record = HitRecord::new(1.0, Vec3::zeros(), Vec3::x(), true, 0.0, 0.0, &material);
+        let samples = 20_000;
+
+        let mean = (0..samples)
+            .map(|_| material.sample(&ray, &hit_record).unwrap())
+            .map(|scatter_record| {
+                let pdf = material.pdf(&ray, &hit_record, &scatter_record.ray.direction);
+                assert!((scatter_record.pdf - pdf).abs() < 1e-3 * pdf);
+                scatter_record.ray.direction.z
+            })
+            .sum::<f32>()
+            / samples as f32;
+
+        assert!((mean - 0.6).abs() < 0.02, "given {}", mean);
+    }
+}
@@ -0,0 +1,53 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::utils::random_unit_vector;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Phase function of a medium that scatters equally in every direction.
+/// `albedo` is the fraction of the extinction that is scattering rather than absorption.
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Vec3) -> Self {
+        Isotropic::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
+        Isotropic { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(&self, _ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = ThreadRng::default();
+        let scattered = Ray::new(hit_record.p, random_unit_vector(&mut rng));
+
+        // Volume scattering is budgeted with diffuse bounces.
+        Some(ScatterRecord::new(
+            scattered,
+            self.albedo.value(hit_record),
+            1.0 / (4.0 * PI),
+            Lobe::Diffuse,
+        ))
+    }
+
+    fn eval(&self, _ray: &Ray, hit_record: &HitRecord, _direction: &Vec3) -> Vec3 {
+        self.albedo.value(hit_record) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: &Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
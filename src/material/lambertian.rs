@@ -1,38 +1,70 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
 use glm::Vec3;
 use rand::rngs::ThreadRng;
 
 use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
 use crate::geometry::hit_record::HitRecord;
-use crate::math::utils::random_unit_vector;
+use crate::math::utils::random_cosine_direction;
 use crate::ray::ray::Ray;
-
-const EPSILON: f32 = 1e-8;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
 
 pub struct Lambertian {
-    albedo: Vec3,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Vec3) -> Self {
+        Lambertian::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
         Lambertian { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Vec3)> {
+    fn sample(&self, _ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
         let mut rng = ThreadRng::default();
-        let scatter_direction = hit_record.normal + random_unit_vector(&mut rng);
-        let result = (
-            Ray::new(
-                hit_record.p,
-                if glm::length2(&scatter_direction) < EPSILON.powi(2) {
-                    hit_record.normal
-                } else {
-                    scatter_direction
-                },
-            ),
-            self.albedo,
-        );
-        Some(result)
+        let direction = random_cosine_direction(&mut rng, &hit_record.normal);
+        let pdf = glm::dot(&direction, &hit_record.normal).max(0.0) / PI;
+
+        // eval / pdf = (albedo / PI * cos) / (cos / PI)
+        Some(ScatterRecord::new(
+            Ray::new(hit_record.p, direction),
+            self.albedo.value(hit_record),
+            pdf,
+            Lobe::Diffuse,
+        ))
+    }
+
+    fn eval(&self, _ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let cosine = glm::dot(direction, &hit_record.normal);
+        if cosine > 0.0 {
+            self.albedo.value(hit_record) * cosine / PI
+        } else {
+            Vec3::zeros()
+        }
+    }
+
+    fn pdf(&self, _ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        glm::dot(direction, &hit_record.normal).max(0.0) / PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::assert_consistent;
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let material = Lambertian::new(Vec3::new(0.2, 0.4, 0.8));
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+
+        assert_consistent(&material, &ray, true, 1e-4);
     }
 }
@@ -1,8 +1,76 @@
+use std::sync::Arc;
+
 use glm::Vec3;
 
 use crate::geometry::hit_record::HitRecord;
+use crate::integrator::fog::Fog;
 use crate::ray::ray::Ray;
 
+use super::scatter_record::ScatterRecord;
+
+/// What fills the space behind the front face of a surface. Paths pick it up when they
+/// transmit into the front face and put it down when they leave through the back face, so a
+/// path through nested surfaces is always attenuated by the innermost one it is in.
+#[derive(Clone, Copy, Default)]
+pub struct Interior<'a> {
+    /// Scattering medium, or `None` for a clear interior.
+    pub fog: Option<&'a Fog>,
+    /// Beer-Lambert absorption coefficient per unit length, on top of that of the fog.
+    pub absorption: Vec3,
+}
+
+impl Interior<'_> {
+    /// Fraction of light of each color left after traveling `distance` through the interior,
+    /// not counting the fog.
+    pub fn absorbed(&self, distance: f32) -> Vec3 {
+        self.absorption.map(|sigma| (-sigma * distance).exp())
+    }
+}
+
 pub trait Material {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Vec3)>;
+    /// Samples an outgoing direction for the incoming `ray`.
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord>;
+
+    /// BSDF times the cosine term for scattering the incoming `ray` into `direction`.
+    fn eval(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: &Vec3) -> Vec3 {
+        Vec3::zeros()
+    }
+
+    /// Solid angle density with which `sample` would have picked `direction`.
+    fn pdf(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    /// Radiance emitted towards the origin of `ray`.
+    fn emitted(&self, _ray: &Ray, _hit_record: &HitRecord) -> Vec3 {
+        Vec3::zeros()
+    }
+
+    /// Contents of the surface, which is clear unless overridden.
+    fn interior(&self) -> Interior<'_> {
+        Interior::default()
+    }
+}
+
+/// Lets several geometries share one material, such as the faces of a `Cuboid`.
+impl<T: Material + ?Sized> Material for Arc<T> {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        (**self).sample(ray, hit_record)
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        (**self).eval(ray, hit_record, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        (**self).pdf(ray, hit_record, direction)
+    }
+
+    fn emitted(&self, ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        (**self).emitted(ray, hit_record)
+    }
+
+    fn interior(&self) -> Interior<'_> {
+        (**self).interior()
+    }
 }
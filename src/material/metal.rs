@@ -1,32 +1,47 @@
 use crate::geometry::hit_record::HitRecord;
 use crate::math::utils::random_unit_vector;
 use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
 
 use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
 use glm::Vec3;
 use rand::rngs::ThreadRng;
+use std::sync::Arc;
 
 pub struct Metal {
-    albedo: Vec3,
-    fuzz: f32,
+    albedo: Arc<dyn Texture>,
+    /// Only the first channel of the texture is used.
+    fuzz: Arc<dyn Texture>,
 }
 
 impl Metal {
+    #[allow(dead_code)]
     pub fn new(albedo: Vec3, fuzz: f32) -> Self {
+        Metal::textured(
+            Arc::new(SolidColor::new(albedo)),
+            Arc::new(SolidColor::new(Vec3::repeat(fuzz))),
+        )
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>, fuzz: Arc<dyn Texture>) -> Self {
         Metal { albedo, fuzz }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Vec3)> {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
         let mut rng = ThreadRng::default();
         let reflection = glm::reflect_vec(&ray.direction, &hit_record.normal);
-        let reflected = reflection.normalize() + (self.fuzz * random_unit_vector(&mut rng));
-        let scattered = Ray::new(hit_record.p, reflected);
+        let fuzz = self.fuzz.value(hit_record).x;
+        let reflected = reflection.normalize() + (fuzz * random_unit_vector(&mut rng));
+        let scattered = Ray::new(hit_record.p, reflected.normalize());
 
         if glm::dot(&scattered.direction, &hit_record.normal) > 0.0 {
-            let result = (scattered, self.albedo);
-            Some(result)
+            // The fuzz perturbation has no closed form density, so it is treated as a delta lobe.
+            let albedo = self.albedo.value(hit_record);
+            Some(ScatterRecord::delta(scattered, albedo, Lobe::Specular))
         } else {
             None
         }
@@ -0,0 +1,131 @@
+use std::f32::consts::PI;
+
+use glm::Vec3;
+
+/// Anisotropic GGX (Trowbridge-Reitz) distribution of microfacet normals.
+/// All directions are expressed in the local shading frame, where the normal is +z.
+pub struct Ggx {
+    alpha_x: f32,
+    alpha_y: f32,
+}
+
+impl Ggx {
+    pub fn new(alpha_x: f32, alpha_y: f32) -> Self {
+        Ggx { alpha_x, alpha_y }
+    }
+
+    /// Maps perceptual `roughness` and `anisotropy` in (0..1) to distribution widths.
+    pub fn from_roughness(roughness: f32, anisotropy: f32) -> Self {
+        let alpha = roughness.powi(2);
+        let aspect = (1.0 - 0.9 * anisotropy).sqrt();
+        Ggx::new(alpha / aspect, alpha * aspect)
+    }
+
+    /// Distributions this narrow are rendered as perfect mirrors.
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < 1e-3
+    }
+
+    pub fn d(&self, wm: &Vec3) -> f32 {
+        if wm.z <= 0.0 {
+            return 0.0;
+        }
+
+        let e = (wm.x / self.alpha_x).powi(2) + (wm.y / self.alpha_y).powi(2) + wm.z.powi(2);
+        1.0 / (PI * self.alpha_x * self.alpha_y * e.powi(2))
+    }
+
+    fn lambda(&self, w: &Vec3) -> f32 {
+        if w.z == 0.0 {
+            return f32::INFINITY;
+        }
+
+        let alpha2_tan2 =
+            ((self.alpha_x * w.x).powi(2) + (self.alpha_y * w.y).powi(2)) / w.z.powi(2);
+        0.5 * (-1.0 + (1.0 + alpha2_tan2).sqrt())
+    }
+
+    pub fn g1(&self, w: &Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Height correlated masking and shadowing.
+    pub fn g(&self, wo: &Vec3, wi: &Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Density of the normals visible from `wo`.
+    pub fn visible_d(&self, wo: &Vec3, wm: &Vec3) -> f32 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * glm::dot(wo, wm).max(0.0) * self.d(wm) / wo.z
+    }
+
+    /// Samples a normal from the distribution of visible normals (Heitz 2018).
+    pub fn sample_visible_normal(&self, wo: &Vec3, u1: f32, u2: f32) -> Vec3 {
+        let vh = Vec3::new(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).normalize();
+        let length2 = vh.x.powi(2) + vh.y.powi(2);
+        let t1 = if length2 > 0.0 {
+            Vec3::new(-vh.y, vh.x, 0.0) / length2.sqrt()
+        } else {
+            Vec3::x()
+        };
+        let t2 = glm::cross(&vh, &t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1.powi(2)).sqrt() + s * r * phi.sin();
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1.powi(2) - p2.powi(2)).max(0.0).sqrt() * vh;
+
+        Vec3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(0.0)).normalize()
+    }
+}
+
+pub fn reflect(wo: &Vec3, wm: &Vec3) -> Vec3 {
+    2.0 * glm::dot(wo, wm) * wm - wo
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn projected_area_is_one() {
+        // The projected microfacet area must match the macro surface: integral of D(m) m.z = 1.
+        let ggx = Ggx::new(0.3, 0.6);
+        let mut rng = rand::thread_rng();
+        let samples = 200_000;
+
+        let estimate: f32 = (0..samples)
+            .map(|_| {
+                // Uniform hemisphere sampling, pdf = 1 / (2 PI).
+                let z: f32 = rng.gen();
+                let phi = 2.0 * PI * rng.gen::<f32>();
+                let r = (1.0 - z * z).sqrt();
+                let wm = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+                ggx.d(&wm) * wm.z * 2.0 * PI
+            })
+            .sum::<f32>()
+            / samples as f32;
+
+        assert!((estimate - 1.0).abs() < 0.05, "given {}", estimate);
+    }
+
+    #[test]
+    fn visible_normals_face_the_viewer() {
+        let ggx = Ggx::from_roughness(0.7, 0.5);
+        let wo = Vec3::new(0.6, -0.3, 0.5).normalize();
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            let wm = ggx.sample_visible_normal(&wo, rng.gen(), rng.gen());
+            assert!((glm::length(&wm) - 1.0).abs() < 1e-4);
+            assert!(glm::dot(&wo, &wm) >= -1e-4, "given {:?}", wm);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::random;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::ScatterRecord;
+
+/// Linear blend of two materials: `weight` 0 is all `a`, 1 is all `b`.
+pub struct Mix<A: Material, B: Material> {
+    a: A,
+    b: B,
+    /// Only the first channel of the texture is used.
+    weight: Arc<dyn Texture>,
+}
+
+impl<A: Material, B: Material> Mix<A, B> {
+    pub fn new(a: A, b: B, weight: f32) -> Self {
+        Mix::textured(a, b, Arc::new(SolidColor::new(Vec3::repeat(weight))))
+    }
+
+    pub fn textured(a: A, b: B, weight: Arc<dyn Texture>) -> Self {
+        Mix { a, b, weight }
+    }
+
+    fn weight(&self, hit_record: &HitRecord) -> f32 {
+        self.weight.value(hit_record).x.clamp(0.0, 1.0)
+    }
+}
+
+impl<A: Material, B: Material> Material for Mix<A, B> {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let weight = self.weight(hit_record);
+        let scatter_record = if random::<f32>() < weight {
+            self.b.sample(ray, hit_record)?
+        } else {
+            self.a.sample(ray, hit_record)?
+        };
+
+        // Picking a side with the probability of its weight cancels the weight for delta lobes.
+        if scatter_record.delta {
+            return Some(scatter_record);
+        }
+
+        let direction = scatter_record.ray.direction;
+        let pdf = self.pdf(ray, hit_record, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let attenuation = self.eval(ray, hit_record, &direction) / pdf;
+        Some(ScatterRecord::new(
+            scatter_record.ray,
+            attenuation,
+            pdf,
+            scatter_record.lobe,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        glm::lerp(
+            &self.a.eval(ray, hit_record, direction),
+            &self.b.eval(ray, hit_record, direction),
+            self.weight(hit_record),
+        )
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let weight = self.weight(hit_record);
+        (1.0 - weight) * self.a.pdf(ray, hit_record, direction)
+            + weight * self.b.pdf(ray, hit_record, direction)
+    }
+
+    fn emitted(&self, ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        glm::lerp(
+            &self.a.emitted(ray, hit_record),
+            &self.b.emitted(ray, hit_record),
+            self.weight(hit_record),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::conductor::Conductor;
+    use super::super::lambertian::Lambertian;
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let material = Mix::new(
+            Lambertian::new(Vec3::new(0.2, 0.4, 0.8)),
+            Conductor::gold(0.3),
+            0.4,
+        );
+        let ray = Ray::new(
+            Vec3::new(0.5, 1.0, 0.0),
+            Vec3::new(-0.5, -1.0, 0.0).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+    }
+
+    #[test]
+    fn weight_selects_material() {
+        let albedo = Vec3::new(0.2, 0.4, 0.8);
+        let material = Mix::new(Lambertian::new(albedo), Conductor::gold(0.0), 0.0);
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let hit_record = hit_record(&material, true);
+
+        for _ in 0..20 {
+            let given = material.sample(&ray, &hit_record).unwrap();
+            assert!(!given.delta);
+            assert!(
+                glm::equal_eps(&albedo, &given.attenuation, 1e-4)
+                    .iter()
+                    .all(|&x| x),
+                "expected {:?}, given {:?}",
+                albedo,
+                given.attenuation
+            );
+        }
+    }
+}
@@ -1,4 +1,19 @@
+pub mod coated;
+pub mod conductor;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod fresnel;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
+#[allow(clippy::module_inception)]
 pub mod material;
 pub mod metal;
+pub mod microfacet;
+pub mod mix;
+pub mod oren_nayar;
+pub mod principled;
+pub mod scatter_record;
+#[cfg(test)]
+pub mod testing;
+pub mod velvet;
@@ -0,0 +1,145 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::math::utils::random_cosine_direction;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Rough diffuse surface of V-shaped Lambertian facets (Oren and Nayar 1994), which flattens
+/// the falloff towards the silhouette of clay, plaster and fabrics.
+pub struct OrenNayar {
+    albedo: Arc<dyn Texture>,
+    /// Standard deviation of the facet angle in radians. Only the first channel is used.
+    roughness: Arc<dyn Texture>,
+}
+
+impl OrenNayar {
+    pub fn new(albedo: Vec3, roughness: f32) -> Self {
+        OrenNayar::textured(
+            Arc::new(SolidColor::new(albedo)),
+            Arc::new(SolidColor::new(Vec3::repeat(roughness))),
+        )
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>, roughness: Arc<dyn Texture>) -> Self {
+        OrenNayar { albedo, roughness }
+    }
+}
+
+impl Material for OrenNayar {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = ThreadRng::default();
+        let onb = Onb::new(&hit_record.normal);
+        let wi = random_cosine_direction(&mut rng, &Vec3::z());
+        let direction = onb.local_to_world(&wi);
+
+        let pdf = self.pdf(ray, hit_record, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord::new(
+            Ray::new(hit_record.p, direction),
+            self.eval(ray, hit_record, &direction) / pdf,
+            pdf,
+            Lobe::Diffuse,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Vec3::zeros();
+        }
+
+        let sigma2 = self.roughness.value(hit_record).x.powi(2);
+        let a = 1.0 - sigma2 / (2.0 * (sigma2 + 0.33));
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let sin_o = (1.0 - wo.z.powi(2)).max(0.0).sqrt();
+        let sin_i = (1.0 - wi.z.powi(2)).max(0.0).sqrt();
+        let cos_phi = if sin_o > 1e-4 && sin_i > 1e-4 {
+            ((wi.x * wo.x + wi.y * wo.y) / (sin_i * sin_o)).max(0.0)
+        } else {
+            0.0
+        };
+        // alpha is the larger of the two polar angles, beta the smaller one.
+        let (sin_alpha, tan_beta) = if wi.z > wo.z {
+            (sin_o, sin_i / wi.z)
+        } else {
+            (sin_i, sin_o / wo.z)
+        };
+
+        self.albedo.value(hit_record) / PI * (a + b * cos_phi * sin_alpha * tan_beta) * wi.z
+    }
+
+    fn pdf(&self, _ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        glm::dot(&direction.normalize(), &hit_record.normal).max(0.0) / PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::lambertian::Lambertian;
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let material = OrenNayar::new(Vec3::new(0.2, 0.4, 0.8), 0.5);
+        let ray = Ray::new(
+            Vec3::new(0.5, 1.0, 0.0),
+            Vec3::new(-0.5, -1.0, 0.0).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+    }
+
+    #[test]
+    fn smooth_matches_lambertian() {
+        let albedo = Vec3::new(0.2, 0.4, 0.8);
+        let material = OrenNayar::new(albedo, 0.0);
+        let lambertian = Lambertian::new(albedo);
+        let ray = Ray::new(
+            Vec3::new(0.5, 1.0, 0.0),
+            Vec3::new(-0.5, -1.0, 0.0).normalize(),
+        );
+        let hit_record = hit_record(&material, true);
+        let direction = Vec3::new(0.3, 0.8, -0.2).normalize();
+
+        let expected = lambertian.eval(&ray, &hit_record, &direction);
+        let given = material.eval(&ray, &hit_record, &direction);
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-6).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+
+    #[test]
+    fn rough_backscatters_more() {
+        let material = OrenNayar::new(Vec3::repeat(1.0), 0.5);
+        let ray = Ray::new(
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(-1.0, -1.0, 0.0).normalize(),
+        );
+        let hit_record = hit_record(&material, true);
+
+        let back = material.eval(&ray, &hit_record, &Vec3::new(1.0, 1.0, 0.0).normalize());
+        let forward = material.eval(&ray, &hit_record, &Vec3::new(-1.0, 1.0, 0.0).normalize());
+        assert!(back.x > forward.x, "given {} and {}", back.x, forward.x);
+    }
+}
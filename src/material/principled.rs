@@ -0,0 +1,410 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::math::utils::random_cosine_direction;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::dielectric::Dielectric;
+use super::fresnel;
+use super::material::Material;
+use super::microfacet::{self, Ggx};
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Keeps the specular lobes out of the delta case, which could not be mixed with the others.
+const MIN_ROUGHNESS: f32 = 0.05;
+const CLEARCOAT_ROUGHNESS: f32 = 0.1;
+const CLEARCOAT_F0: f32 = 0.04;
+
+/// Inputs of the principled BSDF that can be driven by a texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parameter {
+    BaseColor,
+    Metallic,
+    Roughness,
+    Specular,
+    SpecularTint,
+    Sheen,
+    Clearcoat,
+    Transmission,
+}
+
+/// Parameters looked up for a single hit.
+struct Inputs {
+    base_color: Vec3,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    specular_tint: f32,
+    sheen: f32,
+    clearcoat: f32,
+    transmission: f32,
+}
+
+/// Weights of the lobes in the order diffuse, specular, clearcoat, transmission.
+type Weights = [f32; 4];
+
+/// Disney style uber material (Burley 2012, 2015) following the parameters of glTF and Blender.
+/// Scalar parameters are in (0..1) and read the first channel of their texture.
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    metallic: Arc<dyn Texture>,
+    roughness: Arc<dyn Texture>,
+    /// Scales the normal incidence reflectance of the dielectric layer; 0.5 is 4%.
+    specular: Arc<dyn Texture>,
+    /// Tints the dielectric reflection towards the base color.
+    specular_tint: Arc<dyn Texture>,
+    /// Grazing retro-reflection for cloth.
+    sheen: Arc<dyn Texture>,
+    /// Strength of a glossy, colorless coat on top of every other lobe.
+    clearcoat: Arc<dyn Texture>,
+    /// Fraction of the dielectric part that is glass rather than diffuse.
+    transmission: Arc<dyn Texture>,
+    ior: f32,
+}
+
+fn constant(value: f32) -> Arc<dyn Texture> {
+    Arc::new(SolidColor::new(Vec3::repeat(value)))
+}
+
+fn luminance(color: &Vec3) -> f32 {
+    glm::dot(color, &Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+fn schlick_weight(cos_theta: f32) -> f32 {
+    (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+impl Principled {
+    pub fn new(base_color: Vec3) -> Self {
+        Principled {
+            base_color: Arc::new(SolidColor::new(base_color)),
+            metallic: constant(0.0),
+            roughness: constant(0.5),
+            specular: constant(0.5),
+            specular_tint: constant(0.0),
+            sheen: constant(0.0),
+            clearcoat: constant(0.0),
+            transmission: constant(0.0),
+            ior: 1.5,
+        }
+    }
+
+    pub fn set_texture(&mut self, parameter: Parameter, texture: Arc<dyn Texture>) {
+        let slot = match parameter {
+            Parameter::BaseColor => &mut self.base_color,
+            Parameter::Metallic => &mut self.metallic,
+            Parameter::Roughness => &mut self.roughness,
+            Parameter::Specular => &mut self.specular,
+            Parameter::SpecularTint => &mut self.specular_tint,
+            Parameter::Sheen => &mut self.sheen,
+            Parameter::Clearcoat => &mut self.clearcoat,
+            Parameter::Transmission => &mut self.transmission,
+        };
+        *slot = texture;
+    }
+
+    pub fn set_metallic(&mut self, metallic: f32) {
+        self.set_texture(Parameter::Metallic, constant(metallic));
+    }
+
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.set_texture(Parameter::Roughness, constant(roughness));
+    }
+
+    pub fn set_specular(&mut self, specular: f32) {
+        self.set_texture(Parameter::Specular, constant(specular));
+    }
+
+    pub fn set_specular_tint(&mut self, specular_tint: f32) {
+        self.set_texture(Parameter::SpecularTint, constant(specular_tint));
+    }
+
+    pub fn set_sheen(&mut self, sheen: f32) {
+        self.set_texture(Parameter::Sheen, constant(sheen));
+    }
+
+    pub fn set_clearcoat(&mut self, clearcoat: f32) {
+        self.set_texture(Parameter::Clearcoat, constant(clearcoat));
+    }
+
+    pub fn set_transmission(&mut self, transmission: f32) {
+        self.set_texture(Parameter::Transmission, constant(transmission));
+    }
+
+    /// Index of refraction of the transmissive part.
+    pub fn set_ior(&mut self, ior: f32) {
+        self.ior = ior;
+    }
+
+    fn inputs(&self, hit_record: &HitRecord) -> Inputs {
+        let scalar = |texture: &Arc<dyn Texture>| texture.value(hit_record).x.clamp(0.0, 1.0);
+
+        Inputs {
+            base_color: self.base_color.value(hit_record),
+            metallic: scalar(&self.metallic),
+            roughness: scalar(&self.roughness),
+            specular: scalar(&self.specular),
+            specular_tint: scalar(&self.specular_tint),
+            sheen: scalar(&self.sheen),
+            clearcoat: scalar(&self.clearcoat),
+            transmission: scalar(&self.transmission),
+        }
+    }
+
+    /// Energy weights of the lobes and the share of the base layer the coat lets through.
+    fn weights(inputs: &Inputs, wo: &Vec3) -> (Weights, f32) {
+        let dielectric = 1.0 - inputs.metallic;
+        let transmission = dielectric * inputs.transmission;
+        let diffuse = dielectric * (1.0 - inputs.transmission);
+        let specular = 1.0 - transmission;
+        let coat = inputs.clearcoat * fresnel::schlick(wo.z, &Vec3::repeat(CLEARCOAT_F0)).x;
+
+        (
+            [diffuse, specular, inputs.clearcoat, transmission],
+            1.0 - coat,
+        )
+    }
+
+    /// Probabilities of sampling each lobe.
+    fn probabilities(weights: &Weights, base: f32) -> Option<Weights> {
+        let [diffuse, specular, clearcoat, transmission] = *weights;
+        let weights = [
+            diffuse * base,
+            specular * base,
+            clearcoat,
+            transmission * base,
+        ];
+        let total: f32 = weights.iter().sum();
+
+        (total > 0.0).then(|| weights.map(|weight| weight / total))
+    }
+
+    fn specular_f0(inputs: &Inputs) -> Vec3 {
+        let luminance = luminance(&inputs.base_color);
+        let tint = if luminance > 0.0 {
+            inputs.base_color / luminance
+        } else {
+            Vec3::repeat(1.0)
+        };
+        let dielectric =
+            0.08 * inputs.specular * glm::lerp(&Vec3::repeat(1.0), &tint, inputs.specular_tint);
+        glm::lerp(&dielectric, &inputs.base_color, inputs.metallic)
+    }
+
+    fn glass(&self, inputs: &Inputs) -> Dielectric {
+        let mut glass = Dielectric::new(self.ior);
+        glass.set_roughness(inputs.roughness);
+        glass
+    }
+
+    /// Burley's retro-reflective diffuse plus sheen, times the cosine.
+    fn diffuse(inputs: &Inputs, wo: &Vec3, wi: &Vec3) -> Vec3 {
+        if wi.z <= 0.0 {
+            return Vec3::zeros();
+        }
+
+        let wh = (wo + wi).normalize();
+        let cos_d = glm::dot(wi, &wh);
+        let fd90 = 0.5 + 2.0 * inputs.roughness * cos_d.powi(2);
+        let fl = 1.0 + (fd90 - 1.0) * schlick_weight(wi.z);
+        let fv = 1.0 + (fd90 - 1.0) * schlick_weight(wo.z);
+        let sheen = inputs.sheen * schlick_weight(cos_d);
+
+        (inputs.base_color / PI * fl * fv + Vec3::repeat(sheen)) * wi.z
+    }
+
+    /// GGX reflection with Schlick Fresnel, times the cosine, and its density.
+    fn reflection(ggx: &Ggx, f0: &Vec3, wo: &Vec3, wi: &Vec3) -> (Vec3, f32) {
+        if wi.z <= 0.0 {
+            return (Vec3::zeros(), 0.0);
+        }
+
+        let wm = (wo + wi).normalize();
+        let cos_o = glm::dot(wo, &wm);
+        let value = fresnel::schlick(cos_o, f0) * ggx.d(&wm) * ggx.g(wo, wi) / (4.0 * wo.z);
+        let pdf = ggx.visible_d(wo, &wm) / (4.0 * cos_o);
+        (value, pdf)
+    }
+
+    /// Sum of every non-delta lobe for `direction`, and the density of sampling it.
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        inputs: &Inputs,
+        direction: &Vec3,
+    ) -> (Vec3, f32) {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+
+        if wo.z <= 0.0 {
+            return (Vec3::zeros(), 0.0);
+        }
+
+        let (weights, base) = Principled::weights(inputs, &wo);
+        let probabilities = match Principled::probabilities(&weights, base) {
+            Some(probabilities) => probabilities,
+            None => return (Vec3::zeros(), 0.0),
+        };
+
+        let specular = Ggx::from_roughness(inputs.roughness.max(MIN_ROUGHNESS), 0.0);
+        let clearcoat = Ggx::from_roughness(CLEARCOAT_ROUGHNESS, 0.0);
+        let glass = self.glass(inputs);
+
+        let diffuse = Principled::diffuse(inputs, &wo, &wi);
+        let diffuse_pdf = wi.z.max(0.0) / PI;
+        let (reflection, reflection_pdf) =
+            Principled::reflection(&specular, &Principled::specular_f0(inputs), &wo, &wi);
+        let (coat, coat_pdf) =
+            Principled::reflection(&clearcoat, &Vec3::repeat(CLEARCOAT_F0), &wo, &wi);
+        let transmission = glass.eval(ray, hit_record, direction);
+        let transmission_pdf = glass.pdf(ray, hit_record, direction);
+
+        let value = base
+            * (weights[0] * diffuse + weights[1] * reflection + weights[3] * transmission)
+            + weights[2] * coat;
+        let pdf = probabilities[0] * diffuse_pdf
+            + probabilities[1] * reflection_pdf
+            + probabilities[2] * coat_pdf
+            + probabilities[3] * transmission_pdf;
+        (value, pdf)
+    }
+}
+
+impl Material for Principled {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let inputs = self.inputs(hit_record);
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let (weights, base) = Principled::weights(&inputs, &wo);
+        let probabilities = Principled::probabilities(&weights, base)?;
+
+        let mut rng = ThreadRng::default();
+        let u: f32 = rng.gen();
+        let lobe = probabilities
+            .iter()
+            .scan(0.0, |cdf, probability| {
+                *cdf += probability;
+                Some(*cdf)
+            })
+            .position(|cdf| u < cdf)
+            .unwrap_or(3);
+
+        let (direction, kind) = match lobe {
+            0 => {
+                let wi = random_cosine_direction(&mut rng, &Vec3::z());
+                (onb.local_to_world(&wi), Lobe::Diffuse)
+            }
+            1 | 2 => {
+                let roughness = if lobe == 1 {
+                    inputs.roughness.max(MIN_ROUGHNESS)
+                } else {
+                    CLEARCOAT_ROUGHNESS
+                };
+                let ggx = Ggx::from_roughness(roughness, 0.0);
+                let wm = ggx.sample_visible_normal(&wo, rng.gen(), rng.gen());
+                let wi = microfacet::reflect(&wo, &wm);
+                (onb.local_to_world(&wi), Lobe::Specular)
+            }
+            _ => {
+                let scatter_record = self.glass(&inputs).sample(ray, hit_record)?;
+
+                // A smooth interface cannot be weighed against the other lobes.
+                if scatter_record.delta {
+                    let attenuation =
+                        scatter_record.attenuation * base * weights[3] / probabilities[3];
+                    return Some(ScatterRecord::delta(
+                        scatter_record.ray,
+                        attenuation,
+                        scatter_record.lobe,
+                    ));
+                }
+                (scatter_record.ray.direction, scatter_record.lobe)
+            }
+        };
+
+        let (value, pdf) = self.evaluate(ray, hit_record, &inputs, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let scattered = Ray::new(hit_record.p, direction);
+        Some(ScatterRecord::new(scattered, value / pdf, pdf, kind))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let inputs = self.inputs(hit_record);
+        self.evaluate(ray, hit_record, &inputs, direction).0
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        let inputs = self.inputs(hit_record);
+        self.evaluate(ray, hit_record, &inputs, direction).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let mut material = Principled::new(Vec3::new(0.8, 0.3, 0.1));
+        material.set_metallic(0.3);
+        material.set_roughness(0.4);
+        material.set_specular_tint(0.5);
+        material.set_sheen(0.5);
+        material.set_clearcoat(0.7);
+        material.set_transmission(0.5);
+        let ray = Ray::new(
+            Vec3::new(0.4, 1.0, -0.3),
+            Vec3::new(-0.4, -1.0, 0.3).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+        assert_consistent(&material, &ray, false, 1e-3);
+    }
+
+    #[test]
+    fn smooth_transmission_is_delta() {
+        let mut material = Principled::new(Vec3::repeat(1.0));
+        material.set_roughness(0.0);
+        material.set_transmission(1.0);
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let hit_record = hit_record(&material, true);
+
+        let scatter_record = material.sample(&ray, &hit_record).unwrap();
+        assert!(scatter_record.delta);
+    }
+
+    #[test]
+    fn metallic_reflects_base_color_at_normal_incidence() {
+        let mut material = Principled::new(Vec3::new(0.9, 0.6, 0.2));
+        material.set_metallic(1.0);
+        let hit_record = hit_record(&material, true);
+        let given = Principled::specular_f0(&material.inputs(&hit_record));
+        let expected = Vec3::new(0.9, 0.6, 0.2);
+
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-6).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+}
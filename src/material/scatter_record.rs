@@ -0,0 +1,44 @@
+use glm::Vec3;
+
+use crate::ray::ray::Ray;
+
+/// Kind of interaction a sample was drawn from, so integrators can budget bounces per lobe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lobe {
+    Diffuse,
+    Specular,
+    Transmission,
+}
+
+pub struct ScatterRecord {
+    pub ray: Ray,
+    /// Path throughput weight of the sample: `eval / pdf` for non-delta lobes.
+    pub attenuation: Vec3,
+    /// Solid angle density of the sampled direction. Meaningless when `delta` is set.
+    pub pdf: f32,
+    /// Delta distributions (perfect mirrors and glass) cannot be evaluated or light sampled.
+    pub delta: bool,
+    pub lobe: Lobe,
+}
+
+impl ScatterRecord {
+    pub fn new(ray: Ray, attenuation: Vec3, pdf: f32, lobe: Lobe) -> Self {
+        ScatterRecord {
+            ray,
+            attenuation,
+            pdf,
+            delta: false,
+            lobe,
+        }
+    }
+
+    pub fn delta(ray: Ray, attenuation: Vec3, lobe: Lobe) -> Self {
+        ScatterRecord {
+            ray,
+            attenuation,
+            pdf: 1.0,
+            delta: true,
+            lobe,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::ray::ray::Ray;
+
+use super::material::Material;
+
+/// Hit at the origin of a floor facing up, on its front or back face.
+pub fn hit_record(material: &dyn Material, front_face: bool) -> HitRecord<'_> {
+    HitRecord::new(
+        1.0,
+        Vec3::zeros(),
+        Vec3::new(0.0, 1.0, 0.0),
+        front_face,
+        0.0,
+        0.0,
+        material,
+    )
+}
+
+/// Checks that the weights and densities `sample` returns for `ray` match `eval` and `pdf`,
+/// within `tolerance`, relative for densities. The material must not sample delta lobes.
+pub fn assert_consistent(material: &dyn Material, ray: &Ray, front_face: bool, tolerance: f32) {
+    let hit_record = hit_record(material, front_face);
+
+    for _ in 0..200 {
+        let Some(scatter_record) = material.sample(ray, &hit_record) else {
+            continue;
+        };
+        let direction = scatter_record.ray.direction;
+        let pdf = material.pdf(ray, &hit_record, &direction);
+        // Directions at grazing angles have too little density to compare.
+        if pdf <= 1e-4 {
+            continue;
+        }
+        let expected = material.eval(ray, &hit_record, &direction) / pdf;
+        let given = scatter_record.attenuation;
+
+        assert!(!scatter_record.delta);
+        assert!(
+            (scatter_record.pdf - pdf).abs() < tolerance * pdf,
+            "expected {}, given {}",
+            pdf,
+            scatter_record.pdf
+        );
+        assert!(
+            glm::equal_eps(&expected, &given, tolerance)
+                .iter()
+                .all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::f32::consts::PI;
+use std::sync::{Arc, OnceLock};
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+
+use crate::geometry::hit_record::HitRecord;
+use crate::math::onb::Onb;
+use crate::math::utils::random_cosine_direction;
+use crate::ray::ray::Ray;
+use crate::texture::solid_color::SolidColor;
+use crate::texture::texture::Texture;
+
+use super::material::Material;
+use super::scatter_record::{Lobe, ScatterRecord};
+
+/// Keeps the sheen distribution finite.
+const MIN_ROUGHNESS: f32 = 0.07;
+/// Entries along each axis of the sheen albedo table.
+const ALBEDO_SIZE: usize = 16;
+
+/// Cloth: a diffuse base under a sheen of fibers that catch light at grazing angles.
+///
+/// The sheen uses the "Charlie" distribution of Estevez and Kulla 2017 with the visibility term
+/// of Neubelt and Pettineo 2013. The Lambertian base only gets the light the sheen does not
+/// reflect, as measured by the sheen's albedo towards the viewer.
+pub struct Velvet {
+    albedo: Arc<dyn Texture>,
+    sheen: Arc<dyn Texture>,
+    /// Only the first channel of the texture is used.
+    roughness: Arc<dyn Texture>,
+}
+
+impl Velvet {
+    pub fn new(albedo: Vec3, sheen: Vec3, roughness: f32) -> Self {
+        Velvet::textured(
+            Arc::new(SolidColor::new(albedo)),
+            Arc::new(SolidColor::new(sheen)),
+            Arc::new(SolidColor::new(Vec3::repeat(roughness))),
+        )
+    }
+
+    pub fn textured(
+        albedo: Arc<dyn Texture>,
+        sheen: Arc<dyn Texture>,
+        roughness: Arc<dyn Texture>,
+    ) -> Self {
+        Velvet {
+            albedo,
+            sheen,
+            roughness,
+        }
+    }
+
+    fn distribution(alpha: f32, wm: &Vec3) -> f32 {
+        let sin2 = (1.0 - wm.z.powi(2)).max(0.0);
+        (2.0 + 1.0 / alpha) * sin2.powf(0.5 / alpha) / (2.0 * PI)
+    }
+
+    fn visibility(wo: &Vec3, wi: &Vec3) -> f32 {
+        1.0 / (4.0 * (wi.z + wo.z - wi.z * wo.z))
+    }
+
+    /// Sheen reflectance times the cosine, for a white sheen.
+    fn sheen(roughness: f32, wo: &Vec3, wi: &Vec3) -> f32 {
+        let wm = (wo + wi).normalize();
+        Velvet::distribution(roughness.powi(2), &wm) * Velvet::visibility(wo, wi) * wi.z
+    }
+
+    /// Fraction of the light arriving along `wo` that a white sheen reflects, interpolated from
+    /// a table over the cosine of `wo` and the roughness.
+    fn sheen_albedo(cos_o: f32, roughness: f32) -> f32 {
+        static TABLE: OnceLock<Vec<f32>> = OnceLock::new();
+        let last = (ALBEDO_SIZE - 1) as f32;
+        let table = TABLE.get_or_init(|| {
+            (0..ALBEDO_SIZE * ALBEDO_SIZE)
+                .map(|index| {
+                    let cos_o = (index / ALBEDO_SIZE) as f32 / last;
+                    let roughness = (index % ALBEDO_SIZE) as f32 / last;
+                    Velvet::integrate_sheen(cos_o, roughness.max(MIN_ROUGHNESS))
+                })
+                .collect()
+        });
+
+        let x = cos_o.clamp(0.0, 1.0) * last;
+        let y = roughness.clamp(0.0, 1.0) * last;
+        let (i, j) = (
+            (x as usize).min(ALBEDO_SIZE - 2),
+            (y as usize).min(ALBEDO_SIZE - 2),
+        );
+        let (s, t) = (x - i as f32, y - j as f32);
+        let at = |i: usize, j: usize| table[i * ALBEDO_SIZE + j];
+
+        (at(i, j) * (1.0 - t) + at(i, j + 1) * t) * (1.0 - s)
+            + (at(i + 1, j) * (1.0 - t) + at(i + 1, j + 1) * t) * s
+    }
+
+    /// Midpoint rule over the cosine and azimuth of the incoming direction.
+    fn integrate_sheen(cos_o: f32, roughness: f32) -> f32 {
+        let steps = 64;
+        let wo = Vec3::new((1.0 - cos_o.powi(2)).sqrt(), 0.0, cos_o.max(1e-4)).normalize();
+        let step = |index: usize| (index as f32 + 0.5) / steps as f32;
+
+        let sum: f32 = (0..steps * steps)
+            .map(|index| {
+                let cos_i = step(index / steps);
+                let phi = 2.0 * PI * step(index % steps);
+                let sin_i = (1.0 - cos_i.powi(2)).sqrt();
+                let wi = Vec3::new(sin_i * phi.cos(), sin_i * phi.sin(), cos_i);
+                Velvet::sheen(roughness, &wo, &wi)
+            })
+            .sum();
+        sum * 2.0 * PI / (steps * steps) as f32
+    }
+}
+
+impl Material for Velvet {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = ThreadRng::default();
+        let onb = Onb::new(&hit_record.normal);
+        let wi = random_cosine_direction(&mut rng, &Vec3::z());
+        let direction = onb.local_to_world(&wi);
+
+        let pdf = self.pdf(ray, hit_record, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord::new(
+            Ray::new(hit_record.p, direction),
+            self.eval(ray, hit_record, &direction) / pdf,
+            pdf,
+            Lobe::Diffuse,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> Vec3 {
+        let onb = Onb::new(&hit_record.normal);
+        let wo = onb.world_to_local(&-ray.direction.normalize());
+        let wi = onb.world_to_local(&direction.normalize());
+
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Vec3::zeros();
+        }
+
+        let roughness = self.roughness.value(hit_record).x.clamp(MIN_ROUGHNESS, 1.0);
+        let sheen = self.sheen.value(hit_record);
+        let base = (Vec3::repeat(1.0) - sheen * Velvet::sheen_albedo(wo.z, roughness))
+            .map(|weight| weight.max(0.0));
+
+        self.albedo.value(hit_record).component_mul(&base) / PI * wi.z
+            + sheen * Velvet::sheen(roughness, &wo, &wi)
+    }
+
+    fn pdf(&self, _ray: &Ray, hit_record: &HitRecord, direction: &Vec3) -> f32 {
+        glm::dot(&direction.normalize(), &hit_record.normal).max(0.0) / PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{assert_consistent, hit_record};
+    use super::*;
+
+    #[test]
+    fn sample_is_consistent_with_eval_and_pdf() {
+        let material = Velvet::new(Vec3::new(0.2, 0.4, 0.8), Vec3::repeat(0.8), 0.5);
+        let ray = Ray::new(
+            Vec3::new(1.0, 0.3, 0.0),
+            Vec3::new(-1.0, -0.3, 0.0).normalize(),
+        );
+
+        assert_consistent(&material, &ray, true, 1e-3);
+    }
+
+    #[test]
+    fn sheen_brightens_grazing_angles() {
+        let material = Velvet::new(Vec3::zeros(), Vec3::repeat(1.0), 0.5);
+        let hit_record = hit_record(&material, true);
+        let normal = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let grazing = Ray::new(
+            Vec3::new(1.0, 0.1, 0.0),
+            Vec3::new(-1.0, -0.1, 0.0).normalize(),
+        );
+        let direction = Vec3::new(0.0, 1.0, 0.0);
+
+        // Light arrives along the normal in both cases; only the view changes.
+        let front = material.eval(&normal, &hit_record, &direction);
+        let side = material.eval(&grazing, &hit_record, &direction);
+        assert!(side.x > front.x, "given {} and {}", side.x, front.x);
+    }
+
+    #[test]
+    fn sheen_and_base_conserve_energy() {
+        let samples = 20_000;
+
+        for roughness in [0.3, 1.0] {
+            // A white base under a white sheen reflects everything, split between both lobes.
+            let material = Velvet::new(Vec3::repeat(1.0), Vec3::repeat(1.0), roughness);
+            let hit_record = hit_record(&material, true);
+
+            for height in [1.0, 0.3] {
+                let ray = Ray::new(
+                    Vec3::new(1.0, height, 0.0),
+                    Vec3::new(-1.0, -height, 0.0).normalize(),
+                );
+                let albedo = (0..samples)
+                    .filter_map(|_| material.sample(&ray, &hit_record))
+                    .map(|scatter_record| scatter_record.attenuation.x)
+                    .sum::<f32>()
+                    / samples as f32;
+
+                assert!((albedo - 1.0).abs() < 0.03, "expected 1, given {}", albedo);
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use glm::Mat4;
+use glm::Vec3;
+use glm::Vec4;
+
+use crate::ray::ray::Ray;
+
+use super::interval::Interval;
+
+/// Axis aligned bounding box, used to skip geometry that a ray cannot hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Box spanned by the opposite corners `a` and `b`.
+    pub fn new(a: Vec3, b: Vec3) -> Self {
+        Aabb {
+            min: glm::min2(&a, &b),
+            max: glm::max2(&a, &b),
+        }
+    }
+
+    /// Box containing nothing, the identity of `union`.
+    pub fn empty() -> Self {
+        Aabb {
+            min: Vec3::repeat(f32::INFINITY),
+            max: Vec3::repeat(-f32::INFINITY),
+        }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: glm::min2(&self.min, &other.min),
+            max: glm::max2(&self.max, &other.max),
+        }
+    }
+
+    /// Grows flat sides to `delta`, so planar geometry still has a volume to hit.
+    pub fn pad(&self, delta: f32) -> Aabb {
+        let size = self.max - self.min;
+        let padding = size.map(|extent| if extent < delta { delta / 2.0 } else { 0.0 });
+        Aabb {
+            min: self.min - padding,
+            max: self.max + padding,
+        }
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        (self.max - self.min).imax()
+    }
+
+    /// Bounds of this box after an affine `transform`.
+    pub fn transform(&self, transform: &Mat4) -> Aabb {
+        (0..8).fold(Aabb::empty(), |bounds, corner| {
+            let p = Vec3::from_fn(|axis, _| {
+                if corner & (1 << axis) == 0 {
+                    self.min[axis]
+                } else {
+                    self.max[axis]
+                }
+            });
+            let p = (transform * Vec4::new(p.x, p.y, p.z, 1.0)).xyz();
+            bounds.union(&Aabb { min: p, max: p })
+        })
+    }
+
+    /// Slab test for whether `ray` passes through the box within `interval`.
+    pub fn hit(&self, ray: &Ray, interval: &Interval) -> bool {
+        let mut t_min = interval.min;
+        let mut t_max = interval.max;
+
+        for axis in 0..3 {
+            let inverse = 1.0 / ray.direction[axis];
+            let t0 = (self.min[axis] - ray.origin[axis]) * inverse;
+            let t1 = (self.max[axis] - ray.origin[axis]) * inverse;
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+            if t_max < t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_along_and_beside_the_box() {
+        let aabb = Aabb::new(Vec3::repeat(1.0), Vec3::repeat(-1.0));
+        let interval = Interval::new(0.001, f32::MAX);
+        let cases = [
+            (Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), true),
+            (Vec3::new(0.0, 2.0, 5.0), Vec3::new(0.0, 0.0, -1.0), false),
+            (Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 1.0), false),
+            (Vec3::zeros(), Vec3::new(1.0, 1.0, 0.0), true),
+        ];
+
+        for (origin, direction, expected) in cases {
+            let given = aabb.hit(&Ray::new(origin, direction), &interval);
+            assert_eq!(
+                given, expected,
+                "origin {:?}, direction {:?}",
+                origin, direction
+            );
+        }
+    }
+
+    #[test]
+    fn transform_bounds_rotated_box() {
+        let aabb = Aabb::new(Vec3::repeat(-1.0), Vec3::repeat(1.0));
+        let rotation = glm::rotation(std::f32::consts::FRAC_PI_4, &Vec3::z());
+        let given = aabb.transform(&(glm::translation(&Vec3::x()) * rotation));
+        let half_diagonal = 2.0_f32.sqrt();
+        let expected = Aabb::new(
+            Vec3::new(1.0 - half_diagonal, -half_diagonal, -1.0),
+            Vec3::new(1.0 + half_diagonal, half_diagonal, 1.0),
+        );
+
+        for (expected, given) in [(expected.min, given.min), (expected.max, given.max)] {
+            assert!(
+                glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+                "expected {:?}, given {:?}",
+                expected,
+                given
+            );
+        }
+    }
+}
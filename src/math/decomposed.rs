@@ -0,0 +1,98 @@
+use glm::Mat3;
+use glm::Mat4;
+use glm::Quat;
+use glm::Vec3;
+
+/// Affine transform without shear, split into parts that interpolate without distorting the
+/// object in between: a scale, then a rotation, then a translation.
+#[derive(Clone, Copy, Debug)]
+pub struct Decomposed {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Decomposed {
+    pub fn new(matrix: &Mat4) -> Self {
+        let linear = glm::mat4_to_mat3(matrix);
+        let mut scale = Vec3::from_fn(|axis, _| glm::length(&linear.column(axis).into_owned()));
+        // A mirroring transform keeps a proper rotation and flips the sign of one scale.
+        if linear.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+        let rotation = Mat3::from_fn(|row, column| linear[(row, column)] / scale[column]);
+
+        Decomposed {
+            translation: matrix.column(3).xyz(),
+            rotation: glm::mat3_to_quat(&rotation),
+            scale,
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        glm::translation(&self.translation)
+            * glm::quat_to_mat4(&self.rotation)
+            * glm::scaling(&self.scale)
+    }
+
+    /// Blends towards `other` as `t` goes from 0 to 1, along the shortest rotation.
+    pub fn interpolate(&self, other: &Decomposed, t: f32) -> Decomposed {
+        let other_rotation = if self.rotation.dot(&other.rotation) < 0.0 {
+            -other.rotation
+        } else {
+            other.rotation
+        };
+
+        Decomposed {
+            translation: glm::lerp(&self.translation, &other.translation, t),
+            rotation: glm::quat_slerp(&self.rotation, &other_rotation, t),
+            scale: glm::lerp(&self.scale, &other.scale, t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let matrix = glm::translation(&Vec3::new(1.0, 2.0, 3.0))
+            * glm::rotation(0.7, &Vec3::new(1.0, 1.0, 0.0).normalize())
+            * glm::scaling(&Vec3::new(2.0, -1.0, 0.5));
+        let given = Decomposed::new(&matrix).matrix();
+
+        assert!(
+            given
+                .iter()
+                .zip(matrix.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-5),
+            "expected {:?}, given {:?}",
+            matrix,
+            given
+        );
+    }
+
+    #[test]
+    fn interpolate_rotates_halfway() {
+        let start = Decomposed::new(&glm::scaling(&Vec3::repeat(2.0)));
+        let end = Decomposed::new(
+            &(glm::translation(&Vec3::new(0.0, 4.0, 0.0))
+                * glm::rotation(FRAC_PI_2, &Vec3::z())
+                * glm::scaling(&Vec3::repeat(2.0))),
+        );
+        let halfway = start.interpolate(&end, 0.5).matrix();
+
+        let expected = Vec3::new(2.0_f32.sqrt(), 2.0 + 2.0_f32.sqrt(), 0.0);
+        let given = (halfway * glm::vec4(1.0, 0.0, 0.0, 1.0)).xyz();
+
+        assert!(
+            glm::equal_eps(&expected, &given, 1e-5).iter().all(|&x| x),
+            "expected {:?}, given {:?}",
+            expected,
+            given
+        );
+    }
+}
@@ -13,7 +13,6 @@ impl Interval {
         self.max - self.min
     }
 
-    #[allow(dead_code)]
     pub fn contains(&self, x: f32) -> bool {
         self.min <= x && x <= self.max
     }
@@ -21,7 +20,8 @@ impl Interval {
     pub fn surrounds(&self, x: f32) -> bool {
         self.min < x && x < self.max
     }
-    
+
+    #[allow(dead_code)]
     pub fn clamp(&self, x: f32) -> f32 {
         f32::clamp(x, self.min, self.max)
     }
@@ -1,2 +1,5 @@
+pub mod aabb;
+pub mod decomposed;
 pub mod interval;
+pub mod onb;
 pub mod utils;
@@ -0,0 +1,47 @@
+use glm::Vec3;
+
+/// Orthonormal basis around `w`, used to express directions sampled in a local frame.
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn new(normal: &Vec3) -> Self {
+        // Duff et al. 2017, "Building an Orthonormal Basis, Revisited".
+        let w = normal.normalize();
+        let sign = 1.0_f32.copysign(w.z);
+        let a = -1.0 / (sign + w.z);
+        let b = w.x * w.y * a;
+        let u = Vec3::new(1.0 + sign * w.x * w.x * a, sign * b, -sign * w.x);
+        let v = Vec3::new(b, sign + w.y * w.y * a, -w.y);
+
+        Onb { u, v, w }
+    }
+
+    /// Basis around `normal` whose `u` follows `tangent`, projected onto the surface.
+    pub fn with_tangent(normal: &Vec3, tangent: &Vec3) -> Self {
+        let w = normal.normalize();
+        let u = tangent - w * glm::dot(tangent, &w);
+        if glm::length2(&u) < 1e-12 {
+            return Onb::new(normal);
+        }
+        let u = u.normalize();
+        let v = glm::cross(&w, &u);
+
+        Onb { u, v, w }
+    }
+
+    pub fn local_to_world(&self, local: &Vec3) -> Vec3 {
+        local.x * self.u + local.y * self.v + local.z * self.w
+    }
+
+    pub fn world_to_local(&self, world: &Vec3) -> Vec3 {
+        Vec3::new(
+            glm::dot(world, &self.u),
+            glm::dot(world, &self.v),
+            glm::dot(world, &self.w),
+        )
+    }
+}
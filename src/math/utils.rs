@@ -21,18 +21,16 @@ pub fn random_vector_in_unit_disk<T: Rng>(rng: &mut T) -> Vec3 {
         Vec3::new(
             rng.gen_range(interval.min..interval.max),
             rng.gen_range(interval.min..interval.max),
-            rng.gen_range(interval.min..interval.max),
+            0.0,
         )
     })
-    .filter(|vector| glm::length2(vector) < 1.0)
-    .next()
+    .find(|vector| glm::length2(vector) < 1.0)
     .expect("No vector found!")
 }
 
 pub fn random_vector_in_unit_sphere<T: Rng>(rng: &mut T) -> Vec3 {
     iter::repeat_with(|| random_vector(rng, Some(Interval::new(-1.0, 1.0))))
-        .filter(|vector| glm::length2(vector) < 1.0)
-        .next()
+        .find(|vector| glm::length2(vector) < 1.0)
         .expect("No unit vector found!")
 }
 
@@ -40,6 +38,17 @@ pub fn random_unit_vector<T: Rng>(rng: &mut T) -> Vec3 {
     random_vector_in_unit_sphere(rng).normalize()
 }
 
+/// Direction around `normal` with a density proportional to its cosine, by offsetting the
+/// normal with a unit vector. Falls back to the normal when the two nearly cancel out.
+pub fn random_cosine_direction<T: Rng>(rng: &mut T, normal: &Vec3) -> Vec3 {
+    let direction = normal + random_unit_vector(rng);
+    if glm::length2(&direction) < 1e-16 {
+        *normal
+    } else {
+        direction.normalize()
+    }
+}
+
 #[allow(dead_code)]
 pub fn random_vector_on_hemisphere<T: Rng>(rng: &mut T, normal: &Vec3) -> Vec3 {
     let vector = random_unit_vector(rng);
@@ -1,16 +1,35 @@
 use glm::Vec3;
 
+#[derive(Clone, Copy, Debug)]
 pub struct Ray {
     pub origin: Vec3,
     pub direction: Vec3,
+    /// Angle by which the ray cone widens per unit distance, used to pick texture detail.
+    /// Zero for rays whose footprint is unknown.
+    pub spread: f32,
+    /// Width of the ray cone at the origin, left by the bounces before it.
+    pub width: f32,
+    /// Instant within the camera shutter at which the ray is traced, for motion blur.
+    pub time: f32,
 }
 
 impl Ray {
     pub fn new(origin: Vec3, direction: Vec3) -> Self {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            spread: 0.0,
+            width: 0.0,
+            time: 0.0,
+        }
     }
 
     pub fn at(&self, t: f32) -> Vec3 {
         self.origin + (self.direction * t)
     }
+
+    /// Width of the ray cone at `t`.
+    pub fn width_at(&self, t: f32) -> f32 {
+        self.width + self.spread * t * glm::length(&self.direction)
+    }
 }
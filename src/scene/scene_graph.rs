@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use glm::Mat4;
+
+use crate::geometry::bvh::Bvh;
+use crate::geometry::geometry::Geometry;
+use crate::geometry::transformed::Transformed;
+
+/// Handle to a node of a `SceneGraph`. It is invalid once the node is removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+struct Node {
+    name: String,
+    /// Transform relative to the parent node.
+    transform: Mat4,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    geometry: Option<Arc<dyn Geometry + Send>>,
+    visible: bool,
+}
+
+/// Editable hierarchy of named nodes, each placed relative to its parent, that flattens into a
+/// `Bvh` for rendering. Groups are nodes without geometry.
+pub struct SceneGraph {
+    /// Removed nodes leave `None` behind so that other handles stay valid.
+    nodes: Vec<Option<Node>>,
+    names: HashMap<String, NodeId>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        let root = Node {
+            name: String::from("root"),
+            transform: Mat4::identity(),
+            parent: None,
+            children: vec![],
+            geometry: None,
+            visible: true,
+        };
+
+        SceneGraph {
+            nodes: vec![Some(root)],
+            names: HashMap::from([(String::from("root"), NodeId(0))]),
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn add_group(&mut self, parent: NodeId, name: &str, transform: Mat4) -> NodeId {
+        self.add_node(parent, name, transform, None)
+    }
+
+    pub fn add_geometry<G: Geometry + Send + 'static>(
+        &mut self,
+        parent: NodeId,
+        name: &str,
+        transform: Mat4,
+        geometry: G,
+    ) -> NodeId {
+        self.add_node(parent, name, transform, Some(Arc::new(geometry)))
+    }
+
+    /// Adds another node showing `geometry`, which may already be used elsewhere in the graph.
+    pub fn add_instance(
+        &mut self,
+        parent: NodeId,
+        name: &str,
+        transform: Mat4,
+        geometry: Arc<dyn Geometry + Send>,
+    ) -> NodeId {
+        self.add_node(parent, name, transform, Some(geometry))
+    }
+
+    #[allow(dead_code)]
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.names.get(name).copied()
+    }
+
+    #[allow(dead_code)]
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.node(id).name
+    }
+
+    #[allow(dead_code)]
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    #[allow(dead_code)]
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.node(id).children
+    }
+
+    #[allow(dead_code)]
+    pub fn transform(&self, id: NodeId) -> Mat4 {
+        self.node(id).transform
+    }
+
+    pub fn set_transform(&mut self, id: NodeId, transform: Mat4) {
+        self.node_mut(id).transform = transform;
+    }
+
+    /// Transform from the node's frame to the world, through all of its ancestors.
+    #[allow(dead_code)]
+    pub fn world_transform(&self, id: NodeId) -> Mat4 {
+        let node = self.node(id);
+        match node.parent {
+            Some(parent) => self.world_transform(parent) * node.transform,
+            None => node.transform,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_visible(&self, id: NodeId) -> bool {
+        self.node(id).visible
+    }
+
+    /// Hides or shows the node together with its descendants.
+    #[allow(dead_code)]
+    pub fn set_visible(&mut self, id: NodeId, visible: bool) {
+        self.node_mut(id).visible = visible;
+    }
+
+    /// Moves the node and its descendants under `parent`, keeping their relative transforms.
+    #[allow(dead_code)]
+    pub fn set_parent(&mut self, id: NodeId, parent: NodeId) {
+        let mut ancestor = Some(parent);
+        while let Some(current) = ancestor {
+            assert_ne!(current, id, "A node cannot be moved under itself");
+            ancestor = self.node(current).parent;
+        }
+
+        self.detach(id);
+        self.node_mut(parent).children.push(id);
+        self.node_mut(id).parent = Some(parent);
+    }
+
+    /// Removes the node together with its descendants.
+    #[allow(dead_code)]
+    pub fn remove(&mut self, id: NodeId) {
+        assert_ne!(id, self.root(), "The root node cannot be removed");
+        self.detach(id);
+
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            let node = self.nodes[current.0].take().expect("Invalid node handle");
+            self.names.remove(&node.name);
+            pending.extend(node.children);
+        }
+    }
+
+    /// Flattens the visible nodes into world space.
+    pub fn build(&self) -> Bvh {
+        let mut items: Vec<Box<dyn Geometry>> = vec![];
+        let mut pending = vec![(self.root(), Mat4::identity())];
+
+        while let Some((id, parent_transform)) = pending.pop() {
+            let node = self.node(id);
+            if !node.visible {
+                continue;
+            }
+
+            let transform = parent_transform * node.transform;
+            if let Some(geometry) = &node.geometry {
+                if transform == Mat4::identity() {
+                    items.push(Box::new(geometry.clone()));
+                } else {
+                    items.push(Box::new(Transformed::new(geometry.clone(), transform)));
+                }
+            }
+            pending.extend(node.children.iter().map(|&child| (child, transform)));
+        }
+
+        Bvh::new(items)
+    }
+
+    fn add_node(
+        &mut self,
+        parent: NodeId,
+        name: &str,
+        transform: Mat4,
+        geometry: Option<Arc<dyn Geometry + Send>>,
+    ) -> NodeId {
+        assert!(
+            !self.names.contains_key(name),
+            "A node named {} already exists",
+            name
+        );
+
+        let id = NodeId(self.nodes.len());
+        self.node_mut(parent).children.push(id);
+        self.nodes.push(Some(Node {
+            name: String::from(name),
+            transform,
+            parent: Some(parent),
+            children: vec![],
+            geometry,
+            visible: true,
+        }));
+        self.names.insert(String::from(name), id);
+
+        id
+    }
+
+    fn detach(&mut self, id: NodeId) {
+        if let Some(parent) = self.node(id).parent {
+            self.node_mut(parent).children.retain(|&child| child != id);
+        }
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes[id.0].as_ref().expect("Invalid node handle")
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.nodes[id.0].as_mut().expect("Invalid node handle")
+    }
+}
+
+impl Default for SceneGraph {
+    fn default() -> Self {
+        SceneGraph::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glm::Vec3;
+
+    use crate::geometry::sphere::Sphere;
+    use crate::material::lambertian::Lambertian;
+    use crate::math::interval::Interval;
+    use crate::ray::ray::Ray;
+
+    use super::*;
+
+    fn graph() -> SceneGraph {
+        let mut graph = SceneGraph::new();
+        let group = graph.add_group(
+            graph.root(),
+            "group",
+            glm::translation(&Vec3::new(0.0, 0.0, -5.0)),
+        );
+        graph.add_geometry(
+            group,
+            "ball",
+            glm::translation(&Vec3::new(2.0, 0.0, 0.0)),
+            Sphere::new(Vec3::zeros(), 1.0, Lambertian::new(Vec3::repeat(0.5))),
+        );
+        graph
+    }
+
+    fn hit_t(graph: &SceneGraph, origin: Vec3) -> Option<f32> {
+        let ray = Ray::new(origin, Vec3::new(0.0, 0.0, -1.0));
+        graph
+            .build()
+            .hit(&ray, &Interval::new(0.001, f32::MAX))
+            .map(|hit_record| hit_record.t)
+    }
+
+    #[test]
+    fn build_applies_nested_transforms() {
+        let mut graph = graph();
+        let ball = graph.find("ball").unwrap();
+
+        assert_eq!(hit_t(&graph, Vec3::new(2.0, 0.0, 0.0)), Some(4.0));
+        assert_eq!(hit_t(&graph, Vec3::zeros()), None);
+
+        graph.set_transform(ball, Mat4::identity());
+        assert_eq!(hit_t(&graph, Vec3::zeros()), Some(4.0));
+
+        graph.set_visible(graph.find("group").unwrap(), false);
+        assert_eq!(hit_t(&graph, Vec3::zeros()), None);
+    }
+
+    #[test]
+    fn remove_drops_descendants() {
+        let mut graph = graph();
+        let group = graph.find("group").unwrap();
+        let moved = graph.add_group(graph.root(), "moved", Mat4::identity());
+        graph.set_parent(moved, group);
+
+        graph.remove(group);
+
+        assert_eq!(graph.find("group"), None);
+        assert_eq!(graph.find("ball"), None);
+        assert_eq!(graph.find("moved"), None);
+        assert!(graph.children(graph.root()).is_empty());
+        assert_eq!(hit_t(&graph, Vec3::new(2.0, 0.0, 0.0)), None);
+    }
+}
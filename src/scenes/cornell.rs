@@ -0,0 +1,86 @@
+use glm::Vec3;
+
+use crate::geometry::quad::Quad;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::world::World;
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::lambertian::Lambertian;
+
+/// Empty Cornell box, together with the emitters that should be sampled directly.
+pub fn cornell_box() -> (World, World) {
+    let red = Vec3::new(0.65, 0.05, 0.05);
+    let white = Vec3::new(0.73, 0.73, 0.73);
+    let green = Vec3::new(0.12, 0.45, 0.15);
+    let light = Vec3::new(15.0, 15.0, 15.0);
+
+    let light_corner = Vec3::new(343.0, 554.0, 332.0);
+    let light_u = Vec3::new(-130.0, 0.0, 0.0);
+    let light_v = Vec3::new(0.0, 0.0, -105.0);
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Quad::new(
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        Lambertian::new(green),
+    )));
+    world.add(Box::new(Quad::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        Lambertian::new(red),
+    )));
+    world.add(Box::new(Quad::new(
+        light_corner,
+        light_u,
+        light_v,
+        DiffuseLight::new(light),
+    )));
+    world.add(Box::new(Quad::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        Lambertian::new(white),
+    )));
+    world.add(Box::new(Quad::new(
+        Vec3::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        Lambertian::new(white),
+    )));
+    world.add(Box::new(Quad::new(
+        Vec3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Lambertian::new(white),
+    )));
+
+    let lights = World::new(vec![Box::new(Quad::new(
+        light_corner,
+        light_u,
+        light_v,
+        DiffuseLight::new(light),
+    ))]);
+
+    (world, lights)
+}
+
+pub fn cornell_box_scene() -> (World, World) {
+    let (mut world, lights) = cornell_box();
+    let white = Vec3::new(0.73, 0.73, 0.73);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(190.0, 90.0, 190.0),
+        90.0,
+        Dielectric::new(1.5),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(370.0, 120.0, 370.0),
+        120.0,
+        Lambertian::new(white),
+    )));
+
+    (world, lights)
+}
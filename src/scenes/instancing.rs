@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use glm::{Mat4, Vec3};
+
+use crate::geometry::bvh::Bvh;
+use crate::geometry::cuboid::Cuboid;
+use crate::geometry::geometry::Geometry;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::transformed::Transformed;
+use crate::geometry::world::World;
+use crate::material::conductor::Conductor;
+use crate::material::lambertian::Lambertian;
+use crate::material::velvet::Velvet;
+use crate::scene::scene_graph::SceneGraph;
+use crate::texture::checker::Checker;
+
+pub fn instancing_scene() -> World {
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::new(Vec3::new(0.5, 0.5, 0.5)),
+    )));
+
+    // One unit cube shared by a ring of instances, each turned, scaled and painted on its own.
+    let cube: Arc<dyn Geometry + Send> = Arc::new(Cuboid::new(
+        Vec3::repeat(-0.5),
+        Vec3::repeat(0.5),
+        Lambertian::new(Vec3::repeat(0.5)),
+    ));
+    let count = 8;
+    for i in 0..count {
+        let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+        let height = 0.4 + 0.1 * i as f32;
+        let object_to_world = glm::rotation(angle, &Vec3::y())
+            * glm::translation(&Vec3::new(0.0, height / 2.0, -1.8))
+            * glm::rotation(angle, &Vec3::y())
+            * glm::scaling(&Vec3::new(0.4, height, 0.4));
+        let hue = i as f32 / count as f32;
+        let albedo = Vec3::new(hue, 0.3, 1.0 - hue);
+
+        let mut instance = Transformed::new(cube.clone(), object_to_world);
+        match i % 3 {
+            0 => instance.set_material(Lambertian::new(albedo)),
+            1 => instance.set_material(Conductor::from_albedo(albedo, 0.1)),
+            _ => instance.set_material(Velvet::new(albedo, Vec3::repeat(0.8), 0.5)),
+        }
+        world.add(Box::new(instance));
+    }
+
+    world
+}
+
+pub fn scene_graph_scene() -> Bvh {
+    let mut graph = SceneGraph::new();
+    let root = graph.root();
+
+    graph.add_geometry(
+        root,
+        "ground",
+        Mat4::identity(),
+        Sphere::new(
+            Vec3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)),
+        ),
+    );
+
+    // A tower of shared blocks, each turned a little further than the one below.
+    let block: Arc<dyn Geometry + Send> = Arc::new(Cuboid::new(
+        Vec3::new(-0.6, 0.0, -0.2),
+        Vec3::new(0.6, 0.2, 0.2),
+        Lambertian::new(Vec3::new(0.8, 0.5, 0.3)),
+    ));
+    let tower = graph.add_group(root, "tower", Mat4::identity());
+    for level in 0..10 {
+        let transform = glm::translation(&Vec3::new(0.0, 0.2 * level as f32, 0.0))
+            * glm::rotation(0.3 * level as f32, &Vec3::y());
+        graph.add_instance(tower, &format!("block {}", level), transform, block.clone());
+    }
+
+    // Moons parented to an orbit group, which carries them around the tower together.
+    let orbit = graph.add_group(root, "orbit", Mat4::identity());
+    let moons = [
+        (Vec3::new(1.5, 1.0, 0.0), Vec3::new(0.2, 0.3, 0.8)),
+        (Vec3::new(-1.5, 1.4, 0.0), Vec3::new(0.8, 0.2, 0.3)),
+    ];
+    for (i, (offset, albedo)) in moons.into_iter().enumerate() {
+        graph.add_geometry(
+            orbit,
+            &format!("moon {}", i),
+            glm::translation(&offset),
+            Sphere::new(Vec3::zeros(), 0.3, Lambertian::new(albedo)),
+        );
+    }
+
+    graph.set_transform(orbit, glm::rotation(0.8, &Vec3::y()));
+
+    graph.build()
+}
+
+pub fn motion_blur_scene() -> World {
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::textured(Arc::new(Checker::from_colors(
+            0.32,
+            Vec3::new(0.2, 0.3, 0.1),
+            Vec3::new(0.9, 0.9, 0.9),
+        ))),
+    )));
+
+    // Balls bouncing up by different amounts while the shutter is open.
+    for i in 0..5 {
+        let center = Vec3::new(-2.0 + i as f32, 0.3, 0.0);
+        let bounce = Vec3::new(0.0, 0.15 * i as f32, 0.0);
+        world.add(Box::new(Sphere::moving(
+            center,
+            center + bounce,
+            0.3,
+            Lambertian::new(Vec3::new(0.8, 0.3, 0.1)),
+        )));
+    }
+
+    // A spinning top, animated through its instance transform.
+    let position = glm::translation(&Vec3::new(0.0, 0.0, -1.5));
+    let mut top = Transformed::new(
+        Cuboid::new(
+            Vec3::new(-0.3, 0.0, -0.3),
+            Vec3::new(0.3, 0.6, 0.3),
+            Lambertian::new(Vec3::new(0.2, 0.4, 0.8)),
+        ),
+        position,
+    );
+    top.set_motion(position * glm::rotation(1.2, &Vec3::y()));
+    world.add(Box::new(top));
+
+    world
+}
@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+
+use crate::geometry::sphere::Sphere;
+use crate::geometry::world::World;
+use crate::material::coated::Coated;
+use crate::material::conductor::Conductor;
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::material::mix::Mix;
+use crate::material::oren_nayar::OrenNayar;
+use crate::material::principled::{Parameter, Principled};
+use crate::material::velvet::Velvet;
+use crate::texture::checker::Checker;
+use crate::texture::noise_texture::{NoiseTexture, Pattern, Space};
+use crate::texture::solid_color::SolidColor;
+
+pub fn metal_and_glass_scene() -> World {
+    let material_ground = Lambertian::new(Vec3::new(0.8, 0.8, 0.0));
+    let material_center = Lambertian::new(Vec3::new(0.1, 0.2, 0.5));
+    let material_left = Dielectric::new(1.5);
+    let material_bubble = Dielectric::new(1.0 / 1.5);
+    let material_right = Conductor::from_albedo(Vec3::new(0.8, 0.6, 0.2), 1.0);
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(Vec3::new(0.0, -100.5, -1.0), 100.0, material_ground)));
+    world.add(Box::new(Sphere::new(Vec3::new(0.0, 0.0, -1.2), 0.5, material_center)));
+    world.add(Box::new(Sphere::new(Vec3::new(-1.0, 0.0, -1.0), 0.5, material_left)));
+    world.add(Box::new(Sphere::new(Vec3::new(-1.0, 0.0, -1.0), 0.4, material_bubble)));
+    world.add(Box::new(Sphere::new(Vec3::new(1.0, 0.0, -1.0), 0.5, material_right)));
+
+    world
+}
+
+pub fn metals_scene() -> World {
+    let ground = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.5,
+        Vec3::new(0.2, 0.2, 0.2),
+        Vec3::new(0.8, 0.8, 0.8),
+    )));
+    let mut brushed_copper = Conductor::copper(0.4);
+    brushed_copper.set_anisotropy(0.8);
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -100.5, -1.0),
+        100.0,
+        ground,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.1, 0.0, -1.0),
+        0.5,
+        Conductor::gold(0.2),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 0.0, -1.0),
+        0.5,
+        brushed_copper,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.1, 0.0, -1.0),
+        0.5,
+        Conductor::aluminium(0.05),
+    )));
+
+    world
+}
+
+pub fn glass_scene() -> World {
+    let ground = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.5,
+        Vec3::new(0.2, 0.2, 0.2),
+        Vec3::new(0.8, 0.8, 0.8),
+    )));
+    let mut frosted = Dielectric::new(1.5);
+    frosted.set_roughness(0.3);
+    let mut bottle = Dielectric::new(1.5);
+    bottle.set_transmittance(Vec3::new(0.3, 0.7, 0.35), 1.0);
+    let mut liquid = Dielectric::new(1.33);
+    liquid.set_roughness(0.05);
+    liquid.set_transmittance(Vec3::new(0.8, 0.3, 0.1), 0.5);
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -100.5, -1.0),
+        100.0,
+        ground,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.1, 0.0, -1.0),
+        0.5,
+        frosted,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 0.0, -1.0),
+        0.5,
+        bottle,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.1, 0.0, -1.0),
+        0.5,
+        liquid,
+    )));
+
+    world
+}
+
+pub fn principled_scene() -> World {
+    let ground = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.5,
+        Vec3::new(0.2, 0.2, 0.2),
+        Vec3::new(0.8, 0.8, 0.8),
+    )));
+    let mut car_paint = Principled::new(Vec3::new(0.6, 0.05, 0.05));
+    car_paint.set_roughness(0.35);
+    car_paint.set_specular_tint(0.3);
+    car_paint.set_clearcoat(1.0);
+    let mut velvet = Principled::new(Vec3::new(0.1, 0.1, 0.4));
+    velvet.set_texture(
+        Parameter::BaseColor,
+        Arc::new(Checker::from_colors(
+            0.05,
+            Vec3::new(0.1, 0.1, 0.4),
+            Vec3::new(0.4, 0.05, 0.1),
+        )),
+    );
+    velvet.set_roughness(1.0);
+    velvet.set_specular(0.0);
+    velvet.set_sheen(1.0);
+    let mut brass = Principled::new(Vec3::new(0.9, 0.7, 0.3));
+    brass.set_metallic(1.0);
+    brass.set_roughness(0.3);
+    let mut frosted = Principled::new(Vec3::repeat(1.0));
+    frosted.set_roughness(0.2);
+    frosted.set_transmission(1.0);
+    frosted.set_ior(1.45);
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -100.5, -1.0),
+        100.0,
+        ground,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.65, 0.0, -1.0),
+        0.5,
+        car_paint,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-0.55, 0.0, -1.0),
+        0.5,
+        velvet,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.55, 0.0, -1.0),
+        0.5,
+        brass,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.65, 0.0, -1.0),
+        0.5,
+        frosted,
+    )));
+
+    world
+}
+
+pub fn layered_scene() -> World {
+    let ground = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.5,
+        Vec3::new(0.2, 0.2, 0.2),
+        Vec3::new(0.8, 0.8, 0.8),
+    )));
+    // Metallic paint: flakes of metal in a diffuse pigment, under a clear coat.
+    let paint = Vec3::new(0.1, 0.2, 0.6);
+    let car_paint = Coated::new(
+        Mix::new(
+            Lambertian::new(paint),
+            Conductor::from_albedo(paint, 0.4),
+            0.5,
+        ),
+        1.5,
+    );
+    let mut wood = NoiseTexture::new(Pattern::Wood { rings: 8.0 }, 1.0, 2);
+    wood.set_space(Space::Object);
+    wood.set_colors(Vec3::new(0.3, 0.15, 0.05), Vec3::new(0.6, 0.4, 0.2));
+    let mut varnished_wood = Coated::new(Lambertian::textured(Arc::new(wood)), 1.5);
+    varnished_wood.set_roughness(0.15);
+    varnished_wood.set_tint(Vec3::new(0.9, 0.75, 0.5));
+    let mut rust = NoiseTexture::new(
+        Pattern::Fbm {
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        },
+        4.0,
+        3,
+    );
+    rust.set_space(Space::Object);
+    let weathered = Mix::textured(
+        Conductor::aluminium(0.2),
+        Lambertian::new(Vec3::new(0.4, 0.15, 0.05)),
+        Arc::new(rust),
+    );
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -100.5, -1.0),
+        100.0,
+        ground,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.1, 0.0, -1.0),
+        0.5,
+        car_paint,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 0.0, -1.0),
+        0.5,
+        varnished_wood,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.1, 0.0, -1.0),
+        0.5,
+        weathered,
+    )));
+
+    world
+}
+
+pub fn diffuse_scene() -> World {
+    let ground = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.5,
+        Vec3::new(0.2, 0.2, 0.2),
+        Vec3::new(0.8, 0.8, 0.8),
+    )));
+    let mut cloth = NoiseTexture::new(Pattern::Turbulence { octaves: 4 }, 8.0, 4);
+    cloth.set_space(Space::Object);
+    cloth.set_colors(Vec3::new(0.25, 0.02, 0.05), Vec3::new(0.35, 0.05, 0.08));
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, -100.5, -1.0),
+        100.0,
+        ground,
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.1, 0.0, -1.0),
+        0.5,
+        Lambertian::new(Vec3::new(0.7, 0.45, 0.3)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 0.0, -1.0),
+        0.5,
+        OrenNayar::new(Vec3::new(0.7, 0.45, 0.3), 0.6),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.1, 0.0, -1.0),
+        0.5,
+        Velvet::textured(
+            Arc::new(cloth),
+            Arc::new(SolidColor::new(Vec3::new(0.9, 0.5, 0.6))),
+            Arc::new(SolidColor::new(Vec3::repeat(0.4))),
+        ),
+    )));
+
+    world
+}
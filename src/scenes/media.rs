@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+
+use crate::density::noise_density::NoiseDensity;
+use crate::density::voxel_grid::VoxelGrid;
+use crate::geometry::constant_medium::ConstantMedium;
+use crate::geometry::cuboid::Cuboid;
+use crate::geometry::heterogeneous_medium::HeterogeneousMedium;
+use crate::geometry::quad::Quad;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::world::World;
+use crate::integrator::fog::Fog;
+use crate::material::conductor::Conductor;
+use crate::material::dielectric::Dielectric;
+use crate::material::henyey_greenstein::HenyeyGreenstein;
+use crate::material::isotropic::Isotropic;
+use crate::material::lambertian::Lambertian;
+
+use super::cornell::cornell_box;
+
+pub fn cornell_smoke_scene() -> (World, World) {
+    let (mut world, lights) = cornell_box();
+
+    // Only the shape of a boundary matters, its material is never used.
+    world.add(Box::new(ConstantMedium::new(
+        Cuboid::new(
+            Vec3::new(265.0, 0.0, 295.0),
+            Vec3::new(430.0, 330.0, 460.0),
+            Isotropic::new(Vec3::zeros()),
+        ),
+        0.01,
+        Isotropic::new(Vec3::zeros()),
+    )));
+    world.add(Box::new(ConstantMedium::new(
+        Cuboid::new(
+            Vec3::new(130.0, 0.0, 65.0),
+            Vec3::new(295.0, 165.0, 230.0),
+            Isotropic::new(Vec3::zeros()),
+        ),
+        0.01,
+        Isotropic::new(Vec3::repeat(1.0)),
+    )));
+    world.add(Box::new(ConstantMedium::new(
+        Sphere::new(
+            Vec3::new(400.0, 420.0, 150.0),
+            80.0,
+            Isotropic::new(Vec3::zeros()),
+        ),
+        0.02,
+        HenyeyGreenstein::new(Vec3::new(0.8, 0.9, 0.95), 0.7),
+    )));
+
+    (world, lights)
+}
+
+pub fn cornell_clouds_scene() -> (World, World) {
+    let (mut world, lights) = cornell_box();
+
+    // A plume thinning out with height, baked into a voxel grid.
+    let dimensions = [32, 32, 32];
+    let voxels = (0..dimensions.iter().product::<usize>())
+        .map(|i| {
+            let cell = Vec3::new((i % 32) as f32, (i / 32 % 32) as f32, (i / 1024) as f32);
+            let offset = (cell - Vec3::new(15.5, 0.0, 15.5)) / 16.0;
+            let radius = 0.3 + 0.6 * offset.y;
+            (1.0 - (offset.x.powi(2) + offset.z.powi(2)).sqrt() / radius).max(0.0)
+        })
+        .collect();
+    let plume_min = Vec3::new(120.0, 0.0, 120.0);
+    let plume_max = Vec3::new(300.0, 330.0, 300.0);
+    world.add(Box::new(HeterogeneousMedium::new(
+        Cuboid::new(plume_min, plume_max, Isotropic::new(Vec3::zeros())),
+        VoxelGrid::new(dimensions, voxels, plume_min, plume_max),
+        0.05,
+        Isotropic::new(Vec3::new(0.7, 0.7, 0.7)),
+    )));
+
+    world.add(Box::new(HeterogeneousMedium::new(
+        Sphere::new(
+            Vec3::new(400.0, 300.0, 300.0),
+            120.0,
+            Isotropic::new(Vec3::zeros()),
+        ),
+        NoiseDensity::new(0.01, 5, 0.6, 7),
+        0.05,
+        HenyeyGreenstein::new(Vec3::new(0.9, 0.92, 0.95), 0.6),
+    )));
+
+    (world, lights)
+}
+
+pub fn foggy_cornell_scene() -> (World, World, Fog) {
+    let (mut world, lights) = cornell_box();
+    let white = Vec3::new(0.73, 0.73, 0.73);
+
+    world.add(Box::new(Cuboid::new(
+        Vec3::new(250.0, 0.0, 250.0),
+        Vec3::new(330.0, 400.0, 330.0),
+        Lambertian::new(white),
+    )));
+
+    // Ground fog, thick at the floor and thinning out towards the light.
+    let fog = Fog::exponential(0.002, 0.006, 0.0, Isotropic::new(Vec3::repeat(0.9)));
+
+    (world, lights, fog)
+}
+
+pub fn underwater_scene() -> (World, Arc<Fog>) {
+    let water = Arc::new(Fog::homogeneous(
+        0.15,
+        Isotropic::new(Vec3::new(0.2, 0.7, 0.8)),
+    ));
+    let mut surface = Dielectric::new(1.33);
+    surface.set_interior(water.clone());
+
+    let mut world = World::new(vec![]);
+
+    world.add(Box::new(Quad::new(
+        Vec3::new(-50.0, 0.0, -50.0),
+        Vec3::new(100.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 100.0),
+        Lambertian::new(Vec3::new(0.76, 0.7, 0.5)),
+    )));
+    // The surface faces up, so rays from below see its back face and leave the water.
+    world.add(Box::new(Quad::new(
+        Vec3::new(-50.0, 3.0, -50.0),
+        Vec3::new(0.0, 0.0, 100.0),
+        Vec3::new(100.0, 0.0, 0.0),
+        surface,
+    )));
+    // A clear glass ball in the water.
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.2, 0.5, -1.2),
+        0.5,
+        Dielectric::new(1.5),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-1.2, 0.6, -1.0),
+        0.6,
+        Lambertian::new(Vec3::new(0.6, 0.2, 0.2)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(1.0, 0.8, -2.5),
+        0.8,
+        Conductor::from_albedo(Vec3::new(0.8, 0.6, 0.2), 0.2),
+    )));
+
+    (world, water)
+}
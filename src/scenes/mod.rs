@@ -0,0 +1,6 @@
+pub mod cornell;
+pub mod instancing;
+pub mod materials;
+pub mod media;
+pub mod render;
+pub mod spheres;
@@ -0,0 +1,229 @@
+use std::path::Path;
+
+use glm::Vec3;
+
+use crate::animation::sequence::{render_sequence, CameraAnimation, Frame};
+use crate::animation::track::{Interpolation, Track};
+use crate::camera::camera::Camera;
+use crate::camera::stereo::{StereoLayout, StereoRig};
+use crate::color::color_management::{ColorManagement, DisplayTransform};
+use crate::color::color_space::ColorSpace;
+use crate::geometry::cuboid::Cuboid;
+use crate::geometry::geometry::Geometry;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::transformed::Transformed;
+use crate::geometry::world::World;
+use crate::integrator::path_tracer::{LobeDepths, PathTracer};
+use crate::material::conductor::Conductor;
+use crate::material::lambertian::Lambertian;
+use crate::math::decomposed::Decomposed;
+
+use super::cornell::cornell_box_scene;
+use super::instancing::{instancing_scene, motion_blur_scene, scene_graph_scene};
+use super::materials::{
+    diffuse_scene, glass_scene, layered_scene, metal_and_glass_scene, metals_scene,
+    principled_scene,
+};
+use super::media::{
+    cornell_clouds_scene, cornell_smoke_scene, foggy_cornell_scene, underwater_scene,
+};
+use super::spheres::{earth_scene, procedural_scene, random_scene};
+
+/// Renders that can be picked by name on the command line. The first one is the default.
+pub const RENDERS: [(&str, fn()); 19] = [
+    ("metal_and_glass", || {
+        render_material_row(&metal_and_glass_scene())
+    }),
+    ("metals", || render_material_row(&metals_scene())),
+    ("glass", || render_material_row(&glass_scene())),
+    ("principled", || render_material_row(&principled_scene())),
+    ("layered", || render_material_row(&layered_scene())),
+    ("diffuse", || render_material_row(&diffuse_scene())),
+    ("random", || {
+        render_overview(&random_scene(), Vec3::new(13.0, 2.0, 3.0), Vec3::zeros())
+    }),
+    ("procedural", || {
+        render_overview(
+            &procedural_scene(),
+            Vec3::new(13.0, 2.0, 3.0),
+            Vec3::zeros(),
+        )
+    }),
+    ("earth", || {
+        render_overview(&earth_scene(), Vec3::new(0.0, 0.0, 12.0), Vec3::zeros())
+    }),
+    ("instancing", || {
+        render_overview(&instancing_scene(), Vec3::new(0.0, 3.0, 8.0), Vec3::zeros())
+    }),
+    ("scene_graph", || {
+        render_overview(
+            &scene_graph_scene(),
+            Vec3::new(0.0, 2.0, 10.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        )
+    }),
+    ("motion_blur", || {
+        render_overview(
+            &motion_blur_scene(),
+            Vec3::new(0.0, 3.0, 7.0),
+            Vec3::new(0.0, 0.3, -0.5),
+        )
+    }),
+    ("cornell_box", || render_cornell(cornell_box_scene())),
+    ("cornell_smoke", || render_cornell(cornell_smoke_scene())),
+    ("cornell_clouds", || render_cornell(cornell_clouds_scene())),
+    ("foggy_cornell", render_foggy_cornell),
+    ("underwater", render_underwater),
+    ("animation", render_animation),
+    ("stereo_panorama", render_stereo_panorama),
+];
+
+/// Prints `world` under the sky from `position`, looking at `lookat`.
+fn render_sky<T: Geometry>(world: &T, fov: f32, position: Vec3, lookat: Vec3) {
+    const IMAGE_WIDTH: u32 = 600;
+    const IMAGE_HEIGHT: u32 = 275;
+    const SAMPLES_PER_PIXEL: u32 = 50;
+    const MAX_DEPTH: u32 = 64;
+    const RUSSIAN_ROULETTE_DEPTH: u32 = 5;
+
+    let mut camera = Camera::new(IMAGE_WIDTH, IMAGE_HEIGHT, fov, position);
+    let mut integrator = PathTracer::new(World::new(vec![]), MAX_DEPTH);
+    integrator.set_lobe_depths(LobeDepths::new(8, 16, 32));
+    integrator.set_russian_roulette_depth(RUSSIAN_ROULETTE_DEPTH);
+
+    camera.lookat(lookat);
+    camera.set_shutter(0.0, 1.0);
+    camera.set_color_management(ColorManagement::new(
+        ColorSpace::LinearSrgb,
+        DisplayTransform::Srgb,
+        true,
+    ));
+    camera.render(world, &integrator, SAMPLES_PER_PIXEL);
+}
+
+/// Prints a row of spheres around (0, 0, -1), seen from above and to the left.
+fn render_material_row<T: Geometry>(world: &T) {
+    render_sky(
+        world,
+        20.0,
+        Vec3::new(-2.0, 2.0, 1.0),
+        Vec3::new(0.0, 0.0, -1.0),
+    );
+}
+
+/// Prints a whole scene from a distance, with a narrow field of view.
+fn render_overview<T: Geometry>(world: &T, position: Vec3, lookat: Vec3) {
+    render_sky(world, 20.0, position, lookat);
+}
+
+/// Prints a Cornell box scene, sampling its emitters directly.
+fn render_cornell((world, lights): (World, World)) {
+    const SAMPLES_PER_PIXEL: u32 = 200;
+
+    let mut camera = Camera::new(400, 400, 40.0, Vec3::new(278.0, 278.0, -800.0));
+    let integrator = PathTracer::new(lights, 16);
+
+    camera.lookat(Vec3::new(278.0, 278.0, 0.0));
+    camera.render(&world, &integrator, SAMPLES_PER_PIXEL);
+}
+
+/// Renders a two second clip into `frames/`.
+fn render_animation() {
+    const IMAGE_WIDTH: u32 = 400;
+    const IMAGE_HEIGHT: u32 = 225;
+    const SAMPLES_PER_PIXEL: u32 = 32;
+    const FPS: f32 = 24.0;
+    const DURATION: f32 = 2.0;
+
+    let ease = Interpolation::Bezier(0.42, 0.0, 0.58, 1.0);
+
+    let mut position = Track::new(Vec3::new(-3.0, 1.5, 4.0));
+    position.set_key(2.0, Vec3::new(3.0, 1.0, 4.0), ease);
+    let mut fov = Track::new(40.0);
+    fov.set_key(2.0, 25.0, ease);
+    let camera = CameraAnimation::new(position, Track::new(Vec3::new(0.0, 0.5, 0.0)), fov);
+    let template = Camera::new(IMAGE_WIDTH, IMAGE_HEIGHT, 40.0, Vec3::zeros());
+
+    let start = glm::translation(&Vec3::new(-1.0, 0.5, 0.0));
+    let end = glm::translation(&Vec3::new(1.0, 0.5, 0.0)) * glm::rotation(3.0, &Vec3::y());
+    let mut cube = Track::new(Decomposed::new(&start));
+    cube.set_key(2.0, Decomposed::new(&end), Interpolation::Linear);
+    let mut albedo = Track::new(Vec3::new(0.8, 0.2, 0.1));
+    albedo.set_key(2.0, Vec3::new(0.1, 0.3, 0.8), Interpolation::Linear);
+    let mut roughness = Track::new(0.0);
+    roughness.set_key(1.0, 0.5, ease);
+
+    let frames = render_sequence(
+        Path::new("frames"),
+        (DURATION * FPS) as u32 + 1,
+        DURATION,
+        SAMPLES_PER_PIXEL,
+        |time| {
+            let mut world = World::new(vec![]);
+            world.add(Box::new(Sphere::new(
+                Vec3::new(0.0, -1000.0, 0.0),
+                1000.0,
+                Conductor::from_albedo(Vec3::repeat(0.6), roughness.value(time)),
+            )));
+            world.add(Box::new(Transformed::new(
+                Cuboid::new(
+                    Vec3::repeat(-0.5),
+                    Vec3::repeat(0.5),
+                    Lambertian::new(albedo.value(time)),
+                ),
+                cube.value(time).matrix(),
+            )));
+
+            Frame {
+                camera: camera.camera(&template, time),
+                world,
+                integrator: PathTracer::new(World::new(vec![]), 16),
+            }
+        },
+    );
+    frames.expect("Could not write the frames");
+}
+
+/// Prints an omni-directional stereo panorama, left eye on top, for viewing on a headset.
+fn render_stereo_panorama() {
+    const IMAGE_WIDTH: u32 = 1024;
+    const IMAGE_HEIGHT: u32 = 512;
+    const SAMPLES_PER_PIXEL: u32 = 32;
+
+    let camera = Camera::equirectangular(IMAGE_WIDTH, IMAGE_HEIGHT, Vec3::new(0.0, 0.2, 0.5));
+    let rig = StereoRig::new(0.064, f32::INFINITY, StereoLayout::TopBottom);
+    let integrator = PathTracer::new(World::new(vec![]), 16);
+
+    rig.render(
+        &camera,
+        &metal_and_glass_scene(),
+        &integrator,
+        SAMPLES_PER_PIXEL,
+    );
+}
+
+/// Prints the Cornell box filled with ground fog.
+fn render_foggy_cornell() {
+    const SAMPLES_PER_PIXEL: u32 = 200;
+
+    let (world, lights, fog) = foggy_cornell_scene();
+    let mut camera = Camera::new(400, 400, 40.0, Vec3::new(278.0, 278.0, -800.0));
+    let mut integrator = PathTracer::new(lights, 16);
+    integrator.set_atmosphere(fog);
+
+    camera.lookat(Vec3::new(278.0, 278.0, 0.0));
+    camera.render(&world, &integrator, SAMPLES_PER_PIXEL);
+}
+
+/// Prints a view from under the water surface, looking up through it at the sky.
+fn render_underwater() {
+    const SAMPLES_PER_PIXEL: u32 = 100;
+
+    let (world, water) = underwater_scene();
+    let mut camera = Camera::new(600, 338, 60.0, Vec3::new(0.0, 1.0, 2.0));
+    let mut integrator = PathTracer::new(World::new(vec![]), 32);
+    integrator.set_camera_medium(water);
+
+    camera.lookat(Vec3::new(0.0, 1.5, -2.0));
+    camera.render(&world, &integrator, SAMPLES_PER_PIXEL);
+}
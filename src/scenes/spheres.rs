@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::color::transfer::TransferFunction;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::world::World;
+use crate::material::conductor::Conductor;
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::math::interval::Interval;
+use crate::math::utils::random_vector;
+use crate::texture::checker::Checker;
+use crate::texture::image_texture::{Filter, ImageTexture};
+use crate::texture::noise_texture::{NoiseTexture, Pattern, Space};
+use crate::texture::solid_color::SolidColor;
+
+pub fn random_scene() -> World {
+    let ground_material = Lambertian::textured(Arc::new(Checker::from_colors(
+        0.32,
+        Vec3::new(0.2, 0.3, 0.1),
+        Vec3::new(0.9, 0.9, 0.9),
+    )));
+    let mut world = World::new(vec![Box::new(Sphere::new(
+        Vec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    ))]);
+
+    for a in -11..=11 {
+        for b in -11..=11 {
+            let mut rng = ThreadRng::default();
+            let choose_mat: f32 = rng.gen();
+            let center = Vec3::new(
+                a as f32 + rng.gen_range(0.0..0.9),
+                0.2,
+                b as f32 + rng.gen_range(0.0..0.9),
+            );
+
+            if glm::length(&(center - Vec3::new(4.0, 0.2, 0.0))) > 0.9 {
+                if choose_mat < 0.8 {
+                    let albedo =
+                        random_vector(&mut rng, None).component_mul(&random_vector(&mut rng, None));
+                    let sphere = Sphere::new(center, 0.2, Lambertian::new(albedo));
+                    world.add(Box::new(sphere))
+                } else if choose_mat < 0.95 {
+                    let albedo = random_vector(&mut rng, Some(Interval::new(0.5, 1.0)));
+                    let fuzz = rng.gen_range(0.0..0.5);
+                    let sphere = Sphere::new(center, 0.2, Conductor::from_albedo(albedo, fuzz));
+                    world.add(Box::new(sphere));
+                } else {
+                    let sphere = Sphere::new(center, 0.2, Dielectric::new(1.5));
+                    world.add(Box::new(sphere));
+                }
+            }
+        }
+    }
+
+    let material1 = Dielectric::new(1.5);
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 1.0, 0.0),
+        1.0,
+        material1,
+    )));
+
+    let material2 = Lambertian::new(Vec3::new(0.4, 0.2, 0.1));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-4.0, 1.0, 0.0),
+        1.0,
+        material2,
+    )));
+
+    let material3 = Conductor::from_albedo(Vec3::new(0.7, 0.6, 0.5), 0.0);
+    world.add(Box::new(Sphere::new(
+        Vec3::new(4.0, 1.0, 0.0),
+        1.0,
+        material3,
+    )));
+
+    world
+}
+
+/// Variation of `random_scene` where every small sphere gets its own procedural texture.
+pub fn procedural_scene() -> World {
+    let mut ground_texture = NoiseTexture::new(Pattern::Worley, 2.0, 0);
+    ground_texture.set_colors(Vec3::new(0.2, 0.3, 0.1), Vec3::new(0.9, 0.9, 0.9));
+    let ground_material = Lambertian::textured(Arc::new(ground_texture));
+    let mut world = World::new(vec![Box::new(Sphere::new(
+        Vec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    ))]);
+
+    let patterns = [
+        Pattern::Marble { octaves: 7 },
+        Pattern::Wood { rings: 12.0 },
+        Pattern::Turbulence { octaves: 7 },
+        Pattern::Fbm {
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        },
+    ];
+    let mut rng = ThreadRng::default();
+
+    for a in -11..=11 {
+        for b in -11..=11 {
+            let center = Vec3::new(
+                a as f32 + rng.gen_range(0.0..0.9),
+                0.2,
+                b as f32 + rng.gen_range(0.0..0.9),
+            );
+
+            if glm::length(&(center - Vec3::new(4.0, 0.2, 0.0))) > 0.9 {
+                let pattern = patterns[rng.gen_range(0..patterns.len())];
+                let mut texture = NoiseTexture::new(pattern, rng.gen_range(2.0..8.0), rng.gen());
+                texture.set_space(Space::Object);
+
+                if rng.gen::<f32>() < 0.8 {
+                    let low =
+                        random_vector(&mut rng, None).component_mul(&random_vector(&mut rng, None));
+                    let high = random_vector(&mut rng, Some(Interval::new(0.5, 1.0)));
+                    texture.set_colors(low, high);
+                    let sphere = Sphere::new(center, 0.2, Lambertian::textured(Arc::new(texture)));
+                    world.add(Box::new(sphere));
+                } else {
+                    let albedo = random_vector(&mut rng, Some(Interval::new(0.5, 1.0)));
+                    texture.set_colors(Vec3::zeros(), Vec3::repeat(0.5));
+                    let metal =
+                        Metal::textured(Arc::new(SolidColor::new(albedo)), Arc::new(texture));
+                    world.add(Box::new(Sphere::new(center, 0.2, metal)));
+                }
+            }
+        }
+    }
+
+    let mut marble = NoiseTexture::new(Pattern::Marble { octaves: 7 }, 4.0, 1);
+    marble.set_space(Space::Object);
+    world.add(Box::new(Sphere::new(
+        Vec3::new(0.0, 1.0, 0.0),
+        1.0,
+        Lambertian::textured(Arc::new(marble)),
+    )));
+
+    let mut wood = NoiseTexture::new(Pattern::Wood { rings: 8.0 }, 1.0, 2);
+    wood.set_space(Space::Object);
+    wood.set_colors(Vec3::new(0.3, 0.15, 0.05), Vec3::new(0.6, 0.4, 0.2));
+    world.add(Box::new(Sphere::new(
+        Vec3::new(-4.0, 1.0, 0.0),
+        1.0,
+        Lambertian::textured(Arc::new(wood)),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        Vec3::new(4.0, 1.0, 0.0),
+        1.0,
+        Dielectric::new(1.5),
+    )));
+
+    world
+}
+
+pub fn earth_scene() -> World {
+    let mut earth_texture = ImageTexture::load("earthmap.jpg", TransferFunction::Srgb)
+        .expect("Could not load earthmap.jpg");
+    earth_texture.set_filter(Filter::Trilinear);
+    let earth_surface = Lambertian::textured(Arc::new(earth_texture));
+
+    World::new(vec![Box::new(Sphere::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        2.0,
+        earth_surface,
+    ))])
+}
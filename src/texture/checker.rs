@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+
+use super::solid_color::SolidColor;
+use super::texture::Texture;
+
+/// Alternates between two textures on a 3D grid of cubes with sides of length `scale`.
+pub struct Checker {
+    inverse_scale: f32,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(scale: f32, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Checker {
+            inverse_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f32, even: Vec3, odd: Vec3) -> Self {
+        Checker::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, hit_record: &HitRecord) -> Vec3 {
+        let cell = glm::floor(&(hit_record.p * self.inverse_scale));
+        let sum = cell.x as i32 + cell.y as i32 + cell.z as i32;
+
+        if sum % 2 == 0 {
+            self.even.value(hit_record)
+        } else {
+            self.odd.value(hit_record)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn alternates_between_cells() {
+        let checker = Checker::from_colors(1.0, Vec3::repeat(1.0), Vec3::zeros());
+        let material = Lambertian::new(Vec3::zeros());
+        let cases = [
+            (Vec3::new(0.5, 0.5, 0.5), 1.0),
+            (Vec3::new(1.5, 0.5, 0.5), 0.0),
+            (Vec3::new(-0.5, 0.5, 0.5), 0.0),
+            (Vec3::new(-0.5, -0.5, 0.5), 1.0),
+        ];
+
+        for (p, expected) in cases {
+            let hit_record = HitRecord::new(1.0, p, Vec3::y(), true, 0.0, 0.0, &material);
+            let given = checker.value(&hit_record);
+            assert_eq!(given, Vec3::repeat(expected), "at {:?}", p);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use glm::Vec2;
+use glm::Vec3;
+use image::ImageResult;
+
+use crate::color::transfer::TransferFunction;
+use crate::geometry::hit_record::HitRecord;
+
+use super::texture::Texture;
+
+/// How texel coordinates outside of the image are addressed.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl WrapMode {
+    fn apply(&self, x: i64, size: u32) -> u32 {
+        let size = size as i64;
+        let wrapped = match self {
+            WrapMode::Repeat => x.rem_euclid(size),
+            WrapMode::Clamp => x.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let period = x.rem_euclid(2 * size);
+                if period < size {
+                    period
+                } else {
+                    2 * size - 1 - period
+                }
+            }
+        };
+        wrapped as u32
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+    /// Bilinear lookups in the two mip levels closest to the ray footprint, blended.
+    Trilinear,
+}
+
+struct MipLevel {
+    width: u32,
+    height: u32,
+    pixels: Vec<Vec3>,
+}
+
+impl MipLevel {
+    fn texel(&self, x: i64, y: i64, wrap: WrapMode) -> Vec3 {
+        let x = wrap.apply(x, self.width);
+        let y = wrap.apply(y, self.height);
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Box filters into a level of half the resolution, rounded down. Along an odd side every
+    /// new texel covers two and a fraction of the old ones, so none is dropped.
+    fn downsample(&self) -> MipLevel {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let columns = box_weights(self.width, width);
+        let rows = box_weights(self.height, height);
+        let pixels = rows
+            .iter()
+            .flat_map(|row| columns.iter().map(move |column| (row, column)))
+            .map(|(row, column)| {
+                row.iter()
+                    .flat_map(|&(y, wy)| column.iter().map(move |&(x, wx)| (x, y, wx * wy)))
+                    .map(|(x, y, weight)| self.texel(x, y, WrapMode::Clamp) * weight)
+                    .sum()
+            })
+            .collect();
+
+        MipLevel {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// For each of `to` texels stretched over `from` texels, the texels it covers and the share of
+/// it that each takes.
+fn box_weights(from: u32, to: u32) -> Vec<Vec<(i64, f32)>> {
+    let scale = from as f32 / to as f32;
+    (0..to)
+        .map(|i| {
+            let (start, end) = (i as f32 * scale, (i + 1) as f32 * scale);
+            (start.floor() as u32..(end.ceil() as u32).min(from))
+                .map(|j| {
+                    let overlap = end.min(j as f32 + 1.0) - start.max(j as f32);
+                    (j as i64, overlap / scale)
+                })
+                .filter(|&(_, weight)| weight > 0.0)
+                .collect()
+        })
+        .collect()
+}
+
+/// Maps `u` across and `v` up an image of linear colors, stored in rows from the top.
+pub struct ImageTexture {
+    levels: Vec<MipLevel>,
+    wrap: WrapMode,
+    filter: Filter,
+}
+
+impl ImageTexture {
+    pub fn new(width: u32, height: u32, pixels: Vec<Vec3>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            (width * height) as usize,
+            "Image dimensions do not match the number of pixels"
+        );
+
+        let mut levels = vec![MipLevel {
+            width,
+            height,
+            pixels,
+        }];
+
+        while let Some(level) = levels.last().filter(|l| l.width > 1 || l.height > 1) {
+            levels.push(level.downsample());
+        }
+
+        ImageTexture {
+            levels,
+            wrap: WrapMode::Repeat,
+            filter: Filter::Bilinear,
+        }
+    }
+
+    /// Loads a PNG, JPEG or Radiance HDR file. `transfer` describes how the stored values were
+    /// encoded: `Srgb` for most 8-bit color images, `Linear` for HDR files and data maps.
+    pub fn load<P: AsRef<Path>>(path: P, transfer: TransferFunction) -> ImageResult<Self> {
+        let image = image::open(path)?.into_rgb32f();
+        let (width, height) = image.dimensions();
+        let pixels = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0.map(|channel| transfer.decode(channel));
+                Vec3::new(r, g, b)
+            })
+            .collect();
+
+        Ok(ImageTexture::new(width, height, pixels))
+    }
+
+    #[allow(dead_code)]
+    pub fn set_wrap_mode(&mut self, wrap: WrapMode) {
+        self.wrap = wrap;
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+    }
+
+    fn nearest(&self, level: &MipLevel, u: f32, v: f32) -> Vec3 {
+        let x = (u * level.width as f32).floor() as i64;
+        let y = ((1.0 - v) * level.height as f32).floor() as i64;
+        level.texel(x, y, self.wrap)
+    }
+
+    fn bilinear(&self, level: &MipLevel, u: f32, v: f32) -> Vec3 {
+        // Texel centers sit at half integer coordinates.
+        let x = u * level.width as f32 - 0.5;
+        let y = (1.0 - v) * level.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = glm::lerp(
+            &level.texel(x0, y0, self.wrap),
+            &level.texel(x0 + 1, y0, self.wrap),
+            fx,
+        );
+        let bottom = glm::lerp(
+            &level.texel(x0, y0 + 1, self.wrap),
+            &level.texel(x0 + 1, y0 + 1, self.wrap),
+            fx,
+        );
+        glm::lerp(&top, &bottom, fy)
+    }
+
+    fn trilinear(&self, u: f32, v: f32, footprint: &Vec2) -> Vec3 {
+        let base = &self.levels[0];
+        let texels = (footprint.x * base.width as f32).max(footprint.y * base.height as f32);
+        let max_lod = (self.levels.len() - 1) as f32;
+        let lod = texels.max(1.0).log2().min(max_lod);
+        let lower = lod.floor() as usize;
+        let upper = (lower + 1).min(self.levels.len() - 1);
+
+        glm::lerp(
+            &self.bilinear(&self.levels[lower], u, v),
+            &self.bilinear(&self.levels[upper], u, v),
+            lod.fract(),
+        )
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, hit_record: &HitRecord) -> Vec3 {
+        let (u, v) = (hit_record.u, hit_record.v);
+
+        match self.filter {
+            Filter::Nearest => self.nearest(&self.levels[0], u, v),
+            Filter::Bilinear => self.bilinear(&self.levels[0], u, v),
+            Filter::Trilinear => self.trilinear(u, v, &hit_record.footprint),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient() -> ImageTexture {
+        // 4x2 image whose red channel counts texels from the top left.
+        let pixels = (0..8).map(|i| Vec3::new(i as f32, 0.0, 0.0)).collect();
+        ImageTexture::new(4, 2, pixels)
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let cases = [
+            (WrapMode::Repeat, [3, 0, 1, 2, 3, 0]),
+            (WrapMode::Clamp, [0, 0, 1, 2, 3, 3]),
+            (WrapMode::Mirror, [0, 0, 1, 2, 3, 3]),
+        ];
+
+        for (wrap, expected) in cases {
+            let given = [-1, 0, 1, 2, 3, 4].map(|x| wrap.apply(x, 4));
+            assert_eq!(given, expected, "{:?}", wrap);
+        }
+
+        assert_eq!(WrapMode::Mirror.apply(-2, 4), 1);
+        assert_eq!(WrapMode::Mirror.apply(5, 4), 2);
+    }
+
+    #[test]
+    fn nearest_picks_texel_from_top() {
+        let texture = gradient();
+        let given = texture.nearest(&texture.levels[0], 0.3, 0.9);
+        assert_eq!(given.x, 1.0);
+
+        let given = texture.nearest(&texture.levels[0], 0.3, 0.1);
+        assert_eq!(given.x, 5.0);
+    }
+
+    #[test]
+    fn bilinear_blends_neighbours() {
+        let mut texture = gradient();
+        texture.set_wrap_mode(WrapMode::Clamp);
+
+        // Halfway between the centers of texels 1 and 2 on the top row.
+        let given = texture.bilinear(&texture.levels[0], 0.5, 0.75);
+        assert!((given.x - 1.5).abs() < 1e-5, "given {}", given.x);
+
+        // Centered on the shared corner of texels 1, 2, 5 and 6.
+        let given = texture.bilinear(&texture.levels[0], 0.5, 0.5);
+        assert!((given.x - 3.5).abs() < 1e-5, "given {}", given.x);
+    }
+
+    #[test]
+    fn mip_chain_averages_down_to_one_texel() {
+        let texture = gradient();
+        let sizes: Vec<_> = texture.levels.iter().map(|l| (l.width, l.height)).collect();
+        assert_eq!(sizes, vec![(4, 2), (2, 1), (1, 1)]);
+
+        let given = texture.levels[2].pixels[0].x;
+        assert!((given - 3.5).abs() < 1e-5, "given {}", given);
+    }
+
+    #[test]
+    fn mip_chain_keeps_odd_edges() {
+        let pixels = (0..3)
+            .map(|i| Vec3::new(3.0 * i as f32, 0.0, 0.0))
+            .collect();
+        let texture = ImageTexture::new(3, 1, pixels);
+
+        assert_eq!(texture.levels.len(), 2);
+        let given = texture.levels[1].pixels[0].x;
+        assert!((given - 3.0).abs() < 1e-5, "given {}", given);
+    }
+
+    #[test]
+    fn trilinear_selects_coarser_levels_for_wide_footprints() {
+        let texture = gradient();
+        let fine = texture.trilinear(0.1, 0.9, &Vec2::zeros());
+        let coarse = texture.trilinear(0.1, 0.9, &Vec2::new(1.0, 1.0));
+
+        assert_eq!(fine, texture.bilinear(&texture.levels[0], 0.1, 0.9));
+        assert!((coarse.x - 3.5).abs() < 1e-5, "given {}", coarse.x);
+    }
+}
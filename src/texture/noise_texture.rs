@@ -0,0 +1,185 @@
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+
+use super::perlin::Perlin;
+use super::texture::Texture;
+use super::worley::Worley;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    Perlin,
+    Turbulence {
+        octaves: u32,
+    },
+    Fbm {
+        octaves: u32,
+        lacunarity: f32,
+        gain: f32,
+    },
+    /// Sine stripes along z, distorted by turbulence.
+    Marble {
+        octaves: u32,
+    },
+    /// Concentric rings around the y axis, wobbled by noise.
+    Wood {
+        rings: f32,
+    },
+    Worley,
+}
+
+/// Whether the pattern is fixed in the world or moves with the object it is applied to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Space {
+    World,
+    Object,
+}
+
+/// Procedural solid texture blending between two colors by a noise pattern in (0..1).
+pub struct NoiseTexture {
+    pattern: Pattern,
+    scale: f32,
+    space: Space,
+    low: Vec3,
+    high: Vec3,
+    perlin: Perlin,
+    worley: Worley,
+}
+
+impl NoiseTexture {
+    pub fn new(pattern: Pattern, scale: f32, seed: u64) -> Self {
+        NoiseTexture {
+            pattern,
+            scale,
+            space: Space::World,
+            low: Vec3::zeros(),
+            high: Vec3::repeat(1.0),
+            perlin: Perlin::new(seed),
+            worley: Worley::new(seed),
+        }
+    }
+
+    pub fn set_space(&mut self, space: Space) {
+        self.space = space;
+    }
+
+    pub fn set_colors(&mut self, low: Vec3, high: Vec3) {
+        self.low = low;
+        self.high = high;
+    }
+
+    fn pattern_value(&self, p: &Vec3) -> f32 {
+        let value = match self.pattern {
+            Pattern::Perlin => 0.5 * (1.0 + self.perlin.noise(p)),
+            Pattern::Turbulence { octaves } => self.perlin.turbulence(p, octaves),
+            Pattern::Fbm {
+                octaves,
+                lacunarity,
+                gain,
+            } => 0.5 * (1.0 + self.perlin.fbm(p, octaves, lacunarity, gain)),
+            Pattern::Marble { octaves } => {
+                0.5 * (1.0 + (p.z + 10.0 * self.perlin.turbulence(p, octaves)).sin())
+            }
+            Pattern::Wood { rings } => {
+                let radius = (p.x.powi(2) + p.z.powi(2)).sqrt();
+                let wobble = 0.1 * self.perlin.noise(&(p * 4.0));
+                ((radius + wobble) * rings).fract()
+            }
+            Pattern::Worley => self.worley.distance(p),
+        };
+
+        value.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, hit_record: &HitRecord) -> Vec3 {
+        let p = match self.space {
+            Space::World => hit_record.p,
+            Space::Object => hit_record.object_p,
+        };
+
+        glm::lerp(&self.low, &self.high, self.pattern_value(&(p * self.scale)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::material::lambertian::Lambertian;
+
+    use super::*;
+
+    #[test]
+    fn patterns_stay_between_colors() {
+        let patterns = [
+            Pattern::Perlin,
+            Pattern::Turbulence { octaves: 7 },
+            Pattern::Fbm {
+                octaves: 5,
+                lacunarity: 2.0,
+                gain: 0.5,
+            },
+            Pattern::Marble { octaves: 7 },
+            Pattern::Wood { rings: 4.0 },
+            Pattern::Worley,
+        ];
+
+        for pattern in patterns {
+            let texture = NoiseTexture::new(pattern, 1.0, 0);
+            let values: Vec<f32> = (0..200)
+                .map(|i| {
+                    let p = Vec3::new(i as f32 * 0.37, i as f32 * 0.11, i as f32 * -0.53);
+                    texture.pattern_value(&p)
+                })
+                .collect();
+
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+            assert!(
+                values.iter().any(|&value| value != values[0]),
+                "{:?} is constant",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn patterns_on_the_lattice() {
+        // Perlin noise vanishes at integer points, leaving the underlying shapes.
+        let marble = NoiseTexture::new(Pattern::Marble { octaves: 7 }, 1.0, 0);
+        let given = marble.pattern_value(&Vec3::new(1.0, 2.0, 3.0));
+        let expected = 0.5 * (1.0 + 3.0_f32.sin());
+        assert!(
+            (given - expected).abs() < 1e-5,
+            "expected {}, given {}",
+            expected,
+            given
+        );
+
+        let wood = NoiseTexture::new(Pattern::Wood { rings: 3.0 }, 1.0, 0);
+        let given = wood.pattern_value(&Vec3::new(0.5, 0.0, 0.0));
+        assert!((given - 0.5).abs() < 1e-5, "expected 0.5, given {}", given);
+    }
+
+    #[test]
+    fn object_space_follows_object_p() {
+        let material = Lambertian::new(Vec3::zeros());
+        let mut texture = NoiseTexture::new(Pattern::Perlin, 2.0, 0);
+        texture.set_colors(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        texture.set_space(Space::Object);
+        let object_p = Vec3::new(0.3, 0.7, -0.2);
+        let hit_at = |p: Vec3| {
+            let mut hit_record = HitRecord::new(1.0, p, Vec3::y(), true, 0.0, 0.0, &material);
+            hit_record.object_p = object_p;
+            texture.value(&hit_record)
+        };
+
+        let expected = glm::lerp(
+            &Vec3::new(1.0, 0.0, 0.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            texture.pattern_value(&(object_p * 2.0)),
+        );
+        assert_eq!(hit_at(Vec3::zeros()), expected);
+        assert_eq!(hit_at(Vec3::new(5.0, 1.0, 2.0)), expected);
+    }
+}
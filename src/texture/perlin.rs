@@ -0,0 +1,140 @@
+use glm::Vec3;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::math::interval::Interval;
+use crate::math::utils::random_vector;
+
+const POINT_COUNT: usize = 256;
+
+/// Gradient noise on the integer lattice, as described by Ken Perlin.
+/// Values lie roughly in (-1..1) and vanish at lattice points.
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    permutation_x: Vec<usize>,
+    permutation_y: Vec<usize>,
+    permutation_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let gradients = (0..POINT_COUNT)
+            .map(|_| random_vector(&mut rng, Some(Interval::new(-1.0, 1.0))).normalize())
+            .collect();
+
+        Perlin {
+            gradients,
+            permutation_x: Perlin::permutation(&mut rng),
+            permutation_y: Perlin::permutation(&mut rng),
+            permutation_z: Perlin::permutation(&mut rng),
+        }
+    }
+
+    fn permutation<T: Rng>(rng: &mut T) -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+        for i in (1..POINT_COUNT).rev() {
+            permutation.swap(i, rng.gen_range(0..=i));
+        }
+        permutation
+    }
+
+    pub fn noise(&self, p: &Vec3) -> f32 {
+        let cell = glm::floor(p);
+        let local = p - cell;
+        // Hermite smoothing hides the lattice.
+        let weight = local.map(|x| x * x * (3.0 - 2.0 * x));
+
+        let mut accumulator = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.permutation_x[(cell.x as i64 + di) as usize & 255]
+                        ^ self.permutation_y[(cell.y as i64 + dj) as usize & 255]
+                        ^ self.permutation_z[(cell.z as i64 + dk) as usize & 255];
+                    let offset = local - Vec3::new(di as f32, dj as f32, dk as f32);
+                    let (fi, fj, fk) = (di as f32, dj as f32, dk as f32);
+
+                    accumulator += (fi * weight.x + (1.0 - fi) * (1.0 - weight.x))
+                        * (fj * weight.y + (1.0 - fj) * (1.0 - weight.y))
+                        * (fk * weight.z + (1.0 - fk) * (1.0 - weight.z))
+                        * glm::dot(&self.gradients[index], &offset);
+                }
+            }
+        }
+
+        accumulator
+    }
+
+    /// Sum of `octaves` absolute noise layers, each at twice the frequency and half the weight.
+    pub fn turbulence(&self, p: &Vec3, octaves: u32) -> f32 {
+        let mut accumulator = 0.0;
+        let mut point = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..octaves {
+            accumulator += weight * self.noise(&point).abs();
+            weight *= 0.5;
+            point *= 2.0;
+        }
+
+        accumulator
+    }
+
+    /// Fractional Brownian motion: signed noise layers whose frequency grows by `lacunarity`
+    /// and amplitude by `gain` per octave.
+    pub fn fbm(&self, p: &Vec3, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        let mut accumulator = 0.0;
+        let mut point = *p;
+        let mut amplitude = 1.0;
+
+        for _ in 0..octaves {
+            accumulator += amplitude * self.noise(&point);
+            amplitude *= gain;
+            point *= lacunarity;
+        }
+
+        accumulator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vanishes_at_lattice_points() {
+        let perlin = Perlin::new(0);
+
+        for p in [
+            Vec3::zeros(),
+            Vec3::new(1.0, -3.0, 7.0),
+            Vec3::new(-12.0, 5.0, 300.0),
+        ] {
+            let given = perlin.noise(&p);
+            assert!(given.abs() < 1e-6, "at {:?}, given {}", p, given);
+        }
+    }
+
+    #[test]
+    fn is_deterministic_per_seed() {
+        let p = Vec3::new(0.3, 1.7, -2.2);
+
+        assert_eq!(Perlin::new(7).noise(&p), Perlin::new(7).noise(&p));
+        assert_ne!(Perlin::new(7).noise(&p), Perlin::new(8).noise(&p));
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let perlin = Perlin::new(0);
+
+        for i in 0..1000 {
+            let p = Vec3::new(i as f32 * 0.37, i as f32 * 0.11, i as f32 * -0.53);
+            let noise = perlin.noise(&p);
+            let turbulence = perlin.turbulence(&p, 7);
+
+            assert!((-1.0..=1.0).contains(&noise), "given {}", noise);
+            assert!((0.0..2.0).contains(&turbulence), "given {}", turbulence);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+
+use super::texture::Texture;
+
+pub struct SolidColor {
+    albedo: Vec3,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Vec3) -> Self {
+        SolidColor { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _hit_record: &HitRecord) -> Vec3 {
+        self.albedo
+    }
+}
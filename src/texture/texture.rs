@@ -0,0 +1,7 @@
+use glm::Vec3;
+
+use crate::geometry::hit_record::HitRecord;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, hit_record: &HitRecord) -> Vec3;
+}
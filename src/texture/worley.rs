@@ -0,0 +1,76 @@
+use glm::Vec3;
+
+/// Cellular noise: the distance from a point to the closest of a set of feature points,
+/// one scattered randomly inside each cell of the integer lattice.
+pub struct Worley {
+    seed: u64,
+}
+
+impl Worley {
+    pub fn new(seed: u64) -> Self {
+        Worley { seed }
+    }
+
+    /// Distance to the nearest feature point, which is at most `sqrt(3)`.
+    pub fn distance(&self, p: &Vec3) -> f32 {
+        let cell = glm::floor(p);
+        let mut closest = f32::MAX;
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbour = cell + Vec3::new(dx as f32, dy as f32, dz as f32);
+                    let feature = neighbour + self.feature_offset(&neighbour);
+                    closest = closest.min(glm::distance2(p, &feature));
+                }
+            }
+        }
+
+        closest.sqrt()
+    }
+
+    fn feature_offset(&self, cell: &Vec3) -> Vec3 {
+        let mut state = self.seed;
+        for coordinate in [cell.x, cell.y, cell.z] {
+            state = splitmix64(state ^ (coordinate as i64 as u64));
+        }
+
+        let mut unit = || {
+            state = splitmix64(state);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        Vec3::new(unit(), unit(), unit())
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vanishes_at_feature_points() {
+        let worley = Worley::new(3);
+        let cell = Vec3::new(2.0, -1.0, 5.0);
+        let feature = cell + worley.feature_offset(&cell);
+
+        assert!(worley.distance(&feature) < 1e-6);
+    }
+
+    #[test]
+    fn is_bounded() {
+        let worley = Worley::new(3);
+
+        for i in 0..1000 {
+            let p = Vec3::new(i as f32 * 0.37, i as f32 * 0.11, i as f32 * -0.53);
+            let given = worley.distance(&p);
+            assert!((0.0..=3.0_f32.sqrt()).contains(&given), "given {}", given);
+        }
+    }
+}